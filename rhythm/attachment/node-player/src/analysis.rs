@@ -0,0 +1,238 @@
+use realfft::{RealFftPlanner, RealToComplex, num_complex::Complex};
+use std::{collections::VecDeque, f32::consts::PI, ops::RangeInclusive, sync::Arc};
+
+pub const BAND_COUNT_RANGE: RangeInclusive<u32> = 1..=256;
+
+pub const REFRESH_RATE_RANGE: RangeInclusive<f64> = 1_f64..=120_f64;
+
+const MINIMUM_FREQUENCY: f32 = 20_f32;
+
+const MAXIMUM_FREQUENCY: f32 = 20_000_f32;
+
+const SILENCE_LEVEL: f32 = -120_f32;
+
+#[derive(Clone, Copy, PartialEq)]
+pub struct AnalysisConfig {
+    band_count: usize,
+    refresh_rate: f64,
+}
+
+impl AnalysisConfig {
+    pub fn new(band_count: u32, refresh_rate: f64) -> Self {
+        Self {
+            band_count: band_count.clamp(*BAND_COUNT_RANGE.start(), *BAND_COUNT_RANGE.end())
+                as usize,
+            refresh_rate: if refresh_rate.is_finite() {
+                refresh_rate.clamp(*REFRESH_RATE_RANGE.start(), *REFRESH_RATE_RANGE.end())
+            } else {
+                30_f64
+            },
+        }
+    }
+}
+
+pub struct AnalysisFrame {
+    spectrum: Vec<f32>,
+    peak: [f32; 2],
+    rms: [f32; 2],
+    clip: [bool; 2],
+}
+
+impl AnalysisFrame {
+    fn get_level(amplitude: f32) -> f32 {
+        if amplitude > 0_f32 {
+            (20_f32 * amplitude.log10()).max(SILENCE_LEVEL)
+        } else {
+            SILENCE_LEVEL
+        }
+    }
+
+    pub fn to_json(&self, progress: f64) -> String {
+        format!(
+            "{{\"progress\":{:.3},\"peak\":[{:.2},{:.2}],\"rms\":[{:.2},{:.2}],\"clip\":[{},{}],\"spectrum\":[{}]}}",
+            progress,
+            Self::get_level(self.peak[0]),
+            Self::get_level(self.peak[1]),
+            Self::get_level(self.rms[0]),
+            Self::get_level(self.rms[1]),
+            self.clip[0],
+            self.clip[1],
+            self.spectrum
+                .iter()
+                .map(|level| format!("{:.1}", level))
+                .collect::<Vec<String>>()
+                .join(",")
+        )
+    }
+}
+
+pub struct AnalysisTap {
+    fft: Arc<dyn RealToComplex<f32>>,
+    window: Vec<f32>,
+    window_gain: f32,
+    band_bin_list: Vec<(usize, usize)>,
+    fft_input: Vec<f32>,
+    fft_output: Vec<Complex<f32>>,
+    fft_scratch: Vec<Complex<f32>>,
+    interval_frame_count: u64,
+    history_capacity: usize,
+    frame_history: VecDeque<[f32; 2]>,
+    written_frame_count: u64,
+    last_analysis_frame_index: Option<u64>,
+}
+
+impl AnalysisTap {
+    pub fn new(config: &AnalysisConfig, sample_rate: u32) -> Self {
+        let fft_size = (sample_rate as usize / 24).next_power_of_two().max(256);
+
+        let fft = RealFftPlanner::<f32>::new().plan_fft_forward(fft_size);
+
+        let window = (0..fft_size)
+            .map(|index| 0.5_f32 - 0.5_f32 * (2_f32 * PI * index as f32 / fft_size as f32).cos())
+            .collect::<Vec<f32>>();
+
+        let window_gain = window.iter().sum::<f32>() / 2_f32;
+
+        let bin_frequency = sample_rate as f32 / fft_size as f32;
+
+        let bin_count = fft_size / 2 + 1;
+
+        let maximum_frequency = MAXIMUM_FREQUENCY.min(sample_rate as f32 / 2_f32);
+
+        let band_bin_list = (0..config.band_count)
+            .map(|band| {
+                let get_edge = |band: usize| {
+                    MINIMUM_FREQUENCY
+                        * (maximum_frequency / MINIMUM_FREQUENCY)
+                            .powf(band as f32 / config.band_count as f32)
+                };
+
+                let start = ((get_edge(band) / bin_frequency).floor() as usize).min(bin_count - 1);
+
+                let end = ((get_edge(band + 1) / bin_frequency).ceil() as usize)
+                    .clamp(start + 1, bin_count);
+
+                (start, end)
+            })
+            .collect();
+
+        let interval_frame_count =
+            ((sample_rate as f64 / config.refresh_rate).round() as u64).max(1);
+
+        Self {
+            fft_input: fft.make_input_vec(),
+            fft_output: fft.make_output_vec(),
+            fft_scratch: fft.make_scratch_vec(),
+            fft,
+            window,
+            window_gain,
+            band_bin_list,
+            interval_frame_count,
+            history_capacity: fft_size.max(interval_frame_count as usize) + sample_rate as usize,
+            frame_history: VecDeque::new(),
+            written_frame_count: 0,
+            last_analysis_frame_index: None,
+        }
+    }
+
+    pub fn push(&mut self, frame: [f32; 2]) {
+        self.frame_history.push_back(frame);
+
+        self.written_frame_count += 1;
+
+        if self.frame_history.len() > self.history_capacity {
+            self.frame_history.pop_front();
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.frame_history.clear();
+
+        self.written_frame_count = 0;
+
+        self.last_analysis_frame_index = None;
+    }
+
+    pub fn request(&mut self, played_frame_index: u64) -> Option<AnalysisFrame> {
+        let played_frame_index = played_frame_index.min(self.written_frame_count);
+
+        if self
+            .last_analysis_frame_index
+            .is_some_and(|last_analysis_frame_index| {
+                played_frame_index < last_analysis_frame_index + self.interval_frame_count
+            })
+        {
+            return None;
+        }
+
+        let level_frame_count = self
+            .last_analysis_frame_index
+            .map_or(self.interval_frame_count, |last_analysis_frame_index| {
+                played_frame_index - last_analysis_frame_index
+            });
+
+        self.last_analysis_frame_index = Some(played_frame_index);
+
+        let history_start_index = self.written_frame_count - self.frame_history.len() as u64;
+
+        let played_offset = played_frame_index.checked_sub(history_start_index)? as usize;
+
+        let mut peak = [0_f32; 2];
+
+        let mut square_sum = [0_f64; 2];
+
+        let level_start = played_offset.saturating_sub(level_frame_count as usize);
+
+        self.frame_history
+            .range(level_start..played_offset)
+            .for_each(|frame| {
+                frame.iter().enumerate().for_each(|(channel, sample)| {
+                    peak[channel] = peak[channel].max(sample.abs());
+
+                    square_sum[channel] += (*sample as f64).powi(2);
+                });
+            });
+
+        let level_length = (played_offset - level_start).max(1) as f64;
+
+        let fft_size = self.window.len();
+
+        self.fft_input
+            .iter_mut()
+            .zip(self.window.iter())
+            .enumerate()
+            .for_each(|(index, (input, window))| {
+                *input = (index + played_offset)
+                    .checked_sub(fft_size)
+                    .and_then(|history_index| self.frame_history.get(history_index))
+                    .map_or(0_f32, |frame| (frame[0] + frame[1]) * 0.5_f32 * window);
+            });
+
+        self.fft
+            .process_with_scratch(
+                &mut self.fft_input,
+                &mut self.fft_output,
+                &mut self.fft_scratch,
+            )
+            .ok()?;
+
+        Some(AnalysisFrame {
+            spectrum: self
+                .band_bin_list
+                .iter()
+                .map(|(start, end)| {
+                    AnalysisFrame::get_level(
+                        self.fft_output[*start..*end]
+                            .iter()
+                            .map(|bin| bin.norm())
+                            .fold(0_f32, f32::max)
+                            / self.window_gain,
+                    )
+                })
+                .collect(),
+            peak,
+            rms: square_sum.map(|square_sum| (square_sum / level_length).sqrt() as f32),
+            clip: peak.map(|peak| peak >= 1_f32),
+        })
+    }
+}
@@ -0,0 +1,357 @@
+use crate::decode::{MediaSource, SourceSignature};
+use image::{ImageFormat, codecs::jpeg::JpegEncoder};
+use napi::{JsObject, bindgen_prelude::*};
+use std::{
+    collections::{HashMap, VecDeque},
+    fs,
+    io::Cursor,
+    path::{Path, PathBuf},
+    sync::{Arc, LazyLock, Mutex, MutexGuard},
+};
+use symphonia::core::meta::StandardVisualKey;
+
+const ARTWORK_CACHE_CAPACITY: usize = 32;
+
+const THUMBNAIL_JPEG_QUALITY: u8 = 90;
+
+const FOLDER_COVER_STEM_LIST: [&str; 4] = ["cover", "folder", "front", "albumart"];
+
+const FOLDER_COVER_EXTENSION_LIST: [&str; 3] = ["jpg", "jpeg", "png"];
+
+static ARTWORK_CACHE: LazyLock<Mutex<ArtworkCache>> =
+    LazyLock::new(|| Mutex::new(ArtworkCache::default()));
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum ArtworkOrigin {
+    Embedded,
+    Folder,
+}
+
+struct Thumbnail {
+    media_type: &'static str,
+    width: u32,
+    height: u32,
+    data: Vec<u8>,
+}
+
+pub struct Artwork {
+    origin: ArtworkOrigin,
+    media_type: String,
+    data: Arc<Vec<u8>>,
+    thumbnail: Option<Arc<Thumbnail>>,
+    thumbnail_path: Option<String>,
+}
+
+struct CachedArtwork {
+    origin: ArtworkOrigin,
+    media_type: String,
+    data: Arc<Vec<u8>>,
+    thumbnail_map: HashMap<u32, Option<Arc<Thumbnail>>>,
+}
+
+#[derive(Default)]
+struct ArtworkCache {
+    entry_map: HashMap<SourceSignature, Option<CachedArtwork>>,
+    key_queue: VecDeque<SourceSignature>,
+}
+
+impl ArtworkCache {
+    fn insert(&mut self, key: SourceSignature, cached_artwork: Option<CachedArtwork>) {
+        if self.entry_map.insert(key.clone(), cached_artwork).is_none() {
+            self.key_queue.push_back(key);
+        }
+
+        while self.key_queue.len() > ARTWORK_CACHE_CAPACITY {
+            if let Some(key) = self.key_queue.pop_front() {
+                self.entry_map.remove(&key);
+            }
+        }
+    }
+}
+
+impl Artwork {
+    fn lock_artwork_cache() -> MutexGuard<'static, ArtworkCache> {
+        ARTWORK_CACHE
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn find_embedded(path: &str) -> Option<(String, Vec<u8>)> {
+        MediaSource::try_from(path).ok().and_then(|media_source| {
+            media_source
+                .visual_vector
+                .iter()
+                .find(|visual| visual.usage == Some(StandardVisualKey::FrontCover))
+                .or_else(|| media_source.visual_vector.first())
+                .map(|visual| (visual.media_type.clone(), visual.data.to_vec()))
+        })
+    }
+
+    fn find_folder(path: &str) -> Option<(String, Vec<u8>)> {
+        let candidate_list = Path::new(path)
+            .parent()
+            .and_then(|directory| fs::read_dir(directory).ok())?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter_map(|candidate_path| {
+                let stem = candidate_path.file_stem()?.to_str()?.to_ascii_lowercase();
+
+                let extension = candidate_path.extension()?.to_str()?.to_ascii_lowercase();
+
+                let stem_rank = FOLDER_COVER_STEM_LIST
+                    .iter()
+                    .position(|candidate_stem| *candidate_stem == stem)?;
+
+                let extension_rank = FOLDER_COVER_EXTENSION_LIST
+                    .iter()
+                    .position(|candidate_extension| *candidate_extension == extension)?;
+
+                Some(((stem_rank, extension_rank), candidate_path))
+            })
+            .collect::<Vec<((usize, usize), PathBuf)>>();
+
+        candidate_list
+            .into_iter()
+            .min_by_key(|(rank, _)| *rank)
+            .and_then(|(_, candidate_path)| fs::read(candidate_path).ok())
+            .and_then(|data| {
+                image::guess_format(&data)
+                    .ok()
+                    .map(|format| (format.to_mime_type().to_string(), data))
+            })
+    }
+
+    fn create_thumbnail(data: &[u8], thumbnail_size: u32) -> Option<Thumbnail> {
+        let image = image::load_from_memory(data).ok()?;
+
+        let image = if image.width() > thumbnail_size || image.height() > thumbnail_size {
+            image.thumbnail(thumbnail_size, thumbnail_size)
+        } else {
+            image
+        };
+
+        let mut thumbnail_data = Cursor::new(Vec::new());
+
+        let media_type = if image.color().has_alpha() {
+            image.write_to(&mut thumbnail_data, ImageFormat::Png).ok()?;
+
+            "image/png"
+        } else {
+            image
+                .to_rgb8()
+                .write_with_encoder(JpegEncoder::new_with_quality(
+                    &mut thumbnail_data,
+                    THUMBNAIL_JPEG_QUALITY,
+                ))
+                .ok()?;
+
+            "image/jpeg"
+        };
+
+        Some(Thumbnail {
+            media_type,
+            width: image.width(),
+            height: image.height(),
+            data: thumbnail_data.into_inner(),
+        })
+    }
+
+    fn persist_thumbnail(
+        signature: &SourceSignature,
+        thumbnail_size: u32,
+        thumbnail: &Thumbnail,
+        cache_directory: &str,
+    ) -> Option<String> {
+        let thumbnail_path = Path::new(cache_directory).join(format!(
+            "{}.{}",
+            signature.get_digest(thumbnail_size),
+            if thumbnail.media_type == "image/png" {
+                "png"
+            } else {
+                "jpg"
+            }
+        ));
+
+        if !thumbnail_path.is_file() {
+            fs::create_dir_all(cache_directory).ok()?;
+
+            fs::write(&thumbnail_path, &thumbnail.data).ok()?;
+        }
+
+        thumbnail_path.to_str().map(String::from)
+    }
+
+    fn extract(
+        path: &str,
+        thumbnail_size: Option<u32>,
+        cache_directory: Option<&str>,
+    ) -> Option<Self> {
+        let key = SourceSignature::new(path)?;
+
+        let cached_entry = Self::lock_artwork_cache()
+            .entry_map
+            .get(&key)
+            .map(|cached_artwork| {
+                cached_artwork.as_ref().map(|cached_artwork| {
+                    (
+                        cached_artwork.origin,
+                        cached_artwork.media_type.clone(),
+                        cached_artwork.data.clone(),
+                    )
+                })
+            });
+
+        let (origin, media_type, data) = match cached_entry {
+            Some(cached_entry) => cached_entry?,
+            None => {
+                let found_entry = Self::find_embedded(path)
+                    .map(|(media_type, data)| (ArtworkOrigin::Embedded, media_type, data))
+                    .or_else(|| {
+                        Self::find_folder(path)
+                            .map(|(media_type, data)| (ArtworkOrigin::Folder, media_type, data))
+                    })
+                    .map(|(origin, media_type, data)| (origin, media_type, Arc::new(data)));
+
+                let mut artwork_cache = Self::lock_artwork_cache();
+
+                if !artwork_cache.entry_map.contains_key(&key) {
+                    artwork_cache.insert(
+                        key.clone(),
+                        found_entry
+                            .as_ref()
+                            .map(|(origin, media_type, data)| CachedArtwork {
+                                origin: *origin,
+                                media_type: media_type.clone(),
+                                data: data.clone(),
+                                thumbnail_map: HashMap::new(),
+                            }),
+                    );
+                }
+
+                found_entry?
+            }
+        };
+
+        let thumbnail = thumbnail_size
+            .filter(|thumbnail_size| *thumbnail_size > 0)
+            .and_then(|thumbnail_size| {
+                let cached_thumbnail = Self::lock_artwork_cache()
+                    .entry_map
+                    .get(&key)
+                    .and_then(|cached_artwork| cached_artwork.as_ref())
+                    .and_then(|cached_artwork| {
+                        cached_artwork.thumbnail_map.get(&thumbnail_size).cloned()
+                    });
+
+                match cached_thumbnail {
+                    Some(cached_thumbnail) => cached_thumbnail,
+                    None => {
+                        let thumbnail = Self::create_thumbnail(&data, thumbnail_size).map(Arc::new);
+
+                        if let Some(cached_artwork) = Self::lock_artwork_cache()
+                            .entry_map
+                            .get_mut(&key)
+                            .and_then(|cached_artwork| cached_artwork.as_mut())
+                        {
+                            cached_artwork
+                                .thumbnail_map
+                                .insert(thumbnail_size, thumbnail.clone());
+                        }
+
+                        thumbnail
+                    }
+                }
+                .map(|thumbnail| (thumbnail_size, thumbnail))
+            });
+
+        Some(Self {
+            origin,
+            media_type,
+            data,
+            thumbnail_path: thumbnail.as_ref().and_then(|(thumbnail_size, thumbnail)| {
+                cache_directory.and_then(|cache_directory| {
+                    Self::persist_thumbnail(&key, *thumbnail_size, thumbnail, cache_directory)
+                })
+            }),
+            thumbnail: thumbnail.map(|(_, thumbnail)| thumbnail),
+        })
+    }
+
+    fn into_object(self, env: &Env) -> Result<JsObject> {
+        let mut artwork = env.create_object()?;
+
+        artwork.set(
+            "origin",
+            match self.origin {
+                ArtworkOrigin::Embedded => "embedded",
+                ArtworkOrigin::Folder => "folder",
+            },
+        )?;
+
+        artwork.set("mediaType", self.media_type)?;
+
+        artwork.set("data", Buffer::from(self.data.to_vec()))?;
+
+        artwork.set(
+            "thumbnail",
+            self.thumbnail
+                .map(|thumbnail| -> Result<JsObject> {
+                    let mut thumbnail_object = env.create_object()?;
+
+                    thumbnail_object.set("mediaType", thumbnail.media_type)?;
+
+                    thumbnail_object.set("width", thumbnail.width)?;
+
+                    thumbnail_object.set("height", thumbnail.height)?;
+
+                    thumbnail_object.set("data", Buffer::from(thumbnail.data.clone()))?;
+
+                    thumbnail_object.set("path", self.thumbnail_path)?;
+
+                    Ok(thumbnail_object)
+                })
+                .transpose()?,
+        )?;
+
+        Ok(artwork)
+    }
+}
+
+pub struct ArtworkTask {
+    path: String,
+    thumbnail_size: Option<u32>,
+    cache_directory: Option<String>,
+}
+
+impl Task for ArtworkTask {
+    type Output = Option<Artwork>;
+    type JsValue = Option<JsObject>;
+
+    fn compute(&mut self) -> Result<Self::Output> {
+        Ok(Artwork::extract(
+            &self.path,
+            self.thumbnail_size,
+            self.cache_directory.as_deref(),
+        ))
+    }
+
+    fn resolve(&mut self, env: Env, output: Self::Output) -> Result<Self::JsValue> {
+        output.map(|artwork| artwork.into_object(&env)).transpose()
+    }
+}
+
+#[cfg_attr(test, allow(dead_code))]
+#[napi(ts_return_type = "Promise<null | { \
+    origin: 'embedded' | 'folder', mediaType: string, data: Buffer, \
+    thumbnail: null | { mediaType: 'image/jpeg' | 'image/png', width: number, height: number, data: Buffer, path: null | string } \
+    }>")]
+pub fn extract_artwork(
+    path: String,
+    thumbnail_size: Option<u32>,
+    cache_directory: Option<String>,
+) -> AsyncTask<ArtworkTask> {
+    AsyncTask::new(ArtworkTask {
+        path,
+        thumbnail_size,
+        cache_directory,
+    })
+}
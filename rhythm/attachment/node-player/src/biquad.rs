@@ -0,0 +1,202 @@
+use std::f64::consts::{FRAC_1_SQRT_2, PI};
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum FilterKind {
+    Peaking,
+    LowShelf,
+    HighShelf,
+    LowPass,
+    HighPass,
+    Notch,
+}
+
+#[derive(Clone)]
+pub struct Biquad {
+    b: [f64; 3],
+    a: [f64; 2],
+    state: [f64; 2],
+}
+
+impl Biquad {
+    pub fn new(b: [f64; 3], a: [f64; 3]) -> Self {
+        Self {
+            b: [b[0] / a[0], b[1] / a[0], b[2] / a[0]],
+            a: [a[1] / a[0], a[2] / a[0]],
+            state: [0_f64; 2],
+        }
+    }
+
+    pub fn design(kind: FilterKind, frequency: f64, gain: f64, q: f64, sample_rate: u32) -> Self {
+        let sample_rate = sample_rate as f64;
+
+        let frequency = frequency.clamp(1_f64, sample_rate * 0.49_f64);
+
+        let q = if q.is_finite() && q > 0_f64 {
+            q
+        } else {
+            FRAC_1_SQRT_2
+        };
+
+        let amplitude = 10_f64.powf(gain / 40_f64);
+
+        let omega = 2_f64 * PI * frequency / sample_rate;
+
+        let (sin, cos) = omega.sin_cos();
+
+        let alpha = sin / (2_f64 * q);
+
+        match kind {
+            FilterKind::Peaking => Self::new(
+                [
+                    1_f64 + alpha * amplitude,
+                    -2_f64 * cos,
+                    1_f64 - alpha * amplitude,
+                ],
+                [
+                    1_f64 + alpha / amplitude,
+                    -2_f64 * cos,
+                    1_f64 - alpha / amplitude,
+                ],
+            ),
+            FilterKind::LowShelf => {
+                let beta = 2_f64 * amplitude.sqrt() * alpha;
+
+                Self::new(
+                    [
+                        amplitude * ((amplitude + 1_f64) - (amplitude - 1_f64) * cos + beta),
+                        2_f64 * amplitude * ((amplitude - 1_f64) - (amplitude + 1_f64) * cos),
+                        amplitude * ((amplitude + 1_f64) - (amplitude - 1_f64) * cos - beta),
+                    ],
+                    [
+                        (amplitude + 1_f64) + (amplitude - 1_f64) * cos + beta,
+                        -2_f64 * ((amplitude - 1_f64) + (amplitude + 1_f64) * cos),
+                        (amplitude + 1_f64) + (amplitude - 1_f64) * cos - beta,
+                    ],
+                )
+            }
+            FilterKind::HighShelf => {
+                let beta = 2_f64 * amplitude.sqrt() * alpha;
+
+                Self::new(
+                    [
+                        amplitude * ((amplitude + 1_f64) + (amplitude - 1_f64) * cos + beta),
+                        -2_f64 * amplitude * ((amplitude - 1_f64) + (amplitude + 1_f64) * cos),
+                        amplitude * ((amplitude + 1_f64) + (amplitude - 1_f64) * cos - beta),
+                    ],
+                    [
+                        (amplitude + 1_f64) - (amplitude - 1_f64) * cos + beta,
+                        2_f64 * ((amplitude - 1_f64) - (amplitude + 1_f64) * cos),
+                        (amplitude + 1_f64) - (amplitude - 1_f64) * cos - beta,
+                    ],
+                )
+            }
+            FilterKind::LowPass => Self::new(
+                [(1_f64 - cos) / 2_f64, 1_f64 - cos, (1_f64 - cos) / 2_f64],
+                [1_f64 + alpha, -2_f64 * cos, 1_f64 - alpha],
+            ),
+            FilterKind::HighPass => Self::new(
+                [(1_f64 + cos) / 2_f64, -(1_f64 + cos), (1_f64 + cos) / 2_f64],
+                [1_f64 + alpha, -2_f64 * cos, 1_f64 - alpha],
+            ),
+            FilterKind::Notch => Self::new(
+                [1_f64, -2_f64 * cos, 1_f64],
+                [1_f64 + alpha, -2_f64 * cos, 1_f64 - alpha],
+            ),
+        }
+    }
+
+    pub fn process(&mut self, x: f64) -> f64 {
+        let y = self.b[0] * x + self.state[0];
+
+        self.state[0] = self.b[1] * x - self.a[0] * y + self.state[1];
+
+        self.state[1] = self.b[2] * x - self.a[1] * y;
+
+        y
+    }
+
+    pub fn reset(&mut self) {
+        self.state = [0_f64; 2];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: u32 = 48_000;
+
+    fn measure_gain(kind: FilterKind, frequency: f64, gain: f64, probe_frequency: f64) -> f64 {
+        let mut biquad = Biquad::design(kind, frequency, gain, FRAC_1_SQRT_2, SAMPLE_RATE);
+
+        let output_list = (0..SAMPLE_RATE as usize * 2)
+            .map(|index| {
+                biquad.process(
+                    (2_f64 * PI * probe_frequency * index as f64 / SAMPLE_RATE as f64).sin(),
+                )
+            })
+            .collect::<Vec<f64>>();
+
+        let tail = &output_list[SAMPLE_RATE as usize..];
+
+        let rms =
+            (tail.iter().map(|sample| sample.powi(2)).sum::<f64>() / tail.len() as f64).sqrt();
+
+        20_f64 * (rms * 2_f64.sqrt()).log10()
+    }
+
+    #[test]
+    fn low_pass_and_high_pass_are_3_db_down_at_the_corner() {
+        assert!(
+            (measure_gain(FilterKind::LowPass, 1_000_f64, 0_f64, 1_000_f64) + 3.01_f64).abs()
+                < 0.05_f64
+        );
+
+        assert!(
+            (measure_gain(FilterKind::HighPass, 1_000_f64, 0_f64, 1_000_f64) + 3.01_f64).abs()
+                < 0.05_f64
+        );
+
+        assert!(measure_gain(FilterKind::LowPass, 1_000_f64, 0_f64, 100_f64).abs() < 0.05_f64);
+
+        assert!(measure_gain(FilterKind::HighPass, 1_000_f64, 0_f64, 10_000_f64).abs() < 0.05_f64);
+    }
+
+    #[test]
+    fn peaking_reaches_its_gain_at_the_center() {
+        assert!(
+            (measure_gain(FilterKind::Peaking, 1_000_f64, 6_f64, 1_000_f64) - 6_f64).abs()
+                < 0.05_f64
+        );
+
+        assert!(
+            (measure_gain(FilterKind::Peaking, 1_000_f64, -6_f64, 1_000_f64) + 6_f64).abs()
+                < 0.05_f64
+        );
+
+        assert!(measure_gain(FilterKind::Peaking, 1_000_f64, 6_f64, 20_f64).abs() < 0.1_f64);
+    }
+
+    #[test]
+    fn shelves_reach_their_gain_away_from_the_corner() {
+        assert!(
+            (measure_gain(FilterKind::LowShelf, 1_000_f64, 6_f64, 20_f64) - 6_f64).abs() < 0.1_f64
+        );
+
+        assert!(measure_gain(FilterKind::LowShelf, 1_000_f64, 6_f64, 20_000_f64).abs() < 0.1_f64);
+
+        assert!(
+            (measure_gain(FilterKind::HighShelf, 1_000_f64, 6_f64, 20_000_f64) - 6_f64).abs()
+                < 0.1_f64
+        );
+
+        assert!(measure_gain(FilterKind::HighShelf, 1_000_f64, 6_f64, 20_f64).abs() < 0.1_f64);
+    }
+
+    #[test]
+    fn notch_rejects_its_center() {
+        assert!(measure_gain(FilterKind::Notch, 1_000_f64, 0_f64, 1_000_f64) < -60_f64);
+
+        assert!(measure_gain(FilterKind::Notch, 1_000_f64, 0_f64, 100_f64).abs() < 0.1_f64);
+    }
+}
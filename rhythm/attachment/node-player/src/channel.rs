@@ -0,0 +1,456 @@
+use crate::dither::Ditherer;
+use std::{collections::VecDeque, f32::consts::FRAC_1_SQRT_2};
+use symphonia::core::{audio::Channels, conv::FromSample, sample::i24};
+
+pub const MAX_CHANNEL_COUNT: usize = 18;
+
+const SPEAKER_MASK_KNOWN: u32 = 0x0003_FFFF;
+
+pub fn resolve_speaker_layout(channel_count: usize, channel_mask: u32) -> Channels {
+    let channels = Channels::from_bits_truncate(channel_mask & SPEAKER_MASK_KNOWN);
+
+    if channels.count() == channel_count {
+        return channels;
+    }
+
+    match channel_count {
+        1 => Channels::FRONT_CENTRE,
+        2 => Channels::FRONT_LEFT | Channels::FRONT_RIGHT,
+        3 => Channels::FRONT_LEFT | Channels::FRONT_RIGHT | Channels::FRONT_CENTRE,
+        4 => {
+            Channels::FRONT_LEFT
+                | Channels::FRONT_RIGHT
+                | Channels::REAR_LEFT
+                | Channels::REAR_RIGHT
+        }
+        5 => {
+            Channels::FRONT_LEFT
+                | Channels::FRONT_RIGHT
+                | Channels::FRONT_CENTRE
+                | Channels::REAR_LEFT
+                | Channels::REAR_RIGHT
+        }
+        6 => {
+            Channels::FRONT_LEFT
+                | Channels::FRONT_RIGHT
+                | Channels::FRONT_CENTRE
+                | Channels::LFE1
+                | Channels::REAR_LEFT
+                | Channels::REAR_RIGHT
+        }
+        7 => {
+            Channels::FRONT_LEFT
+                | Channels::FRONT_RIGHT
+                | Channels::FRONT_CENTRE
+                | Channels::LFE1
+                | Channels::REAR_CENTRE
+                | Channels::SIDE_LEFT
+                | Channels::SIDE_RIGHT
+        }
+        8 => {
+            Channels::FRONT_LEFT
+                | Channels::FRONT_RIGHT
+                | Channels::FRONT_CENTRE
+                | Channels::LFE1
+                | Channels::REAR_LEFT
+                | Channels::REAR_RIGHT
+                | Channels::SIDE_LEFT
+                | Channels::SIDE_RIGHT
+        }
+        _ => Channels::from_bits_truncate(
+            1_u32
+                .checked_shl(channel_count as u32)
+                .map_or(u32::MAX, |bit| bit - 1)
+                & SPEAKER_MASK_KNOWN,
+        ),
+    }
+}
+
+pub trait PackedSample: FromSample<f32> {
+    const BYTE_LENGTH: usize;
+
+    fn pack(self, target: &mut [u8]);
+}
+
+impl PackedSample for i8 {
+    const BYTE_LENGTH: usize = 1;
+
+    fn pack(self, target: &mut [u8]) {
+        target.copy_from_slice(&self.to_ne_bytes());
+    }
+}
+
+impl PackedSample for i16 {
+    const BYTE_LENGTH: usize = 2;
+
+    fn pack(self, target: &mut [u8]) {
+        target.copy_from_slice(&self.to_ne_bytes());
+    }
+}
+
+impl PackedSample for i24 {
+    const BYTE_LENGTH: usize = 3;
+
+    fn pack(self, target: &mut [u8]) {
+        target.copy_from_slice(&self.to_ne_bytes());
+    }
+}
+
+impl PackedSample for i32 {
+    const BYTE_LENGTH: usize = 4;
+
+    fn pack(self, target: &mut [u8]) {
+        target.copy_from_slice(&self.to_ne_bytes());
+    }
+}
+
+impl PackedSample for f32 {
+    const BYTE_LENGTH: usize = 4;
+
+    fn pack(self, target: &mut [u8]) {
+        target.copy_from_slice(&self.to_ne_bytes());
+    }
+}
+
+pub fn interleave<T>(
+    buffer_slice: &mut [u8],
+    source: impl IntoIterator<Item = [f32; 2]>,
+    channel_matrix: &ChannelMatrix,
+    mut ditherer: Option<&mut Ditherer>,
+) where
+    T: PackedSample,
+{
+    let mut source = source.into_iter();
+
+    buffer_slice
+        .chunks_exact_mut(channel_matrix.get_output_count() * T::BYTE_LENGTH)
+        .for_each(|buffer_frame| {
+            let frame = source.next().unwrap_or([0_f32, 0_f32]);
+
+            buffer_frame
+                .chunks_exact_mut(T::BYTE_LENGTH)
+                .zip(channel_matrix.map_frame(&frame))
+                .enumerate()
+                .for_each(|(channel, (sample, value))| {
+                    T::from_sample(match ditherer.as_mut() {
+                        Some(ditherer) => ditherer.process(channel, value),
+                        None => value,
+                    })
+                    .pack(sample);
+                });
+        });
+}
+
+#[napi(object)]
+pub struct StereoImageOption {
+    pub balance: Option<f64>,
+    pub swap: Option<bool>,
+    #[napi(ts_type = "'none' | 'left' | 'right'")]
+    pub invert: Option<String>,
+    pub mono: Option<bool>,
+}
+
+#[derive(Clone, Copy, Default)]
+pub struct StereoImage {
+    balance: f32,
+    channel_is_swapped: bool,
+    inverted_channel: Option<usize>,
+    mono_is_enabled: bool,
+}
+
+impl From<StereoImageOption> for StereoImage {
+    fn from(option: StereoImageOption) -> Self {
+        Self {
+            balance: option
+                .balance
+                .filter(|balance| balance.is_finite())
+                .map_or(0_f32, |balance| balance.clamp(-1_f64, 1_f64) as f32),
+            channel_is_swapped: option.swap.unwrap_or(false),
+            inverted_channel: match option.invert.as_deref() {
+                Some("left") => Some(0),
+                Some("right") => Some(1),
+                _ => None,
+            },
+            mono_is_enabled: option.mono.unwrap_or(false),
+        }
+    }
+}
+
+impl StereoImage {
+    pub fn apply(&self, frame: [f32; 2]) -> [f32; 2] {
+        let [left, right] = if self.channel_is_swapped {
+            [frame[1], frame[0]]
+        } else {
+            frame
+        };
+
+        let [left, right] = if self.mono_is_enabled {
+            let middle = (left + right) * 0.5_f32;
+
+            [middle, middle]
+        } else {
+            [left, right]
+        };
+
+        let mut frame = [
+            left * (1_f32 - self.balance).min(1_f32),
+            right * (1_f32 + self.balance).min(1_f32),
+        ];
+
+        if let Some(inverted_channel) = self.inverted_channel {
+            frame[inverted_channel] = -frame[inverted_channel];
+        }
+
+        frame
+    }
+}
+
+pub struct ChannelMatrix {
+    coefficient_matrix: Vec<Vec<f32>>,
+}
+
+impl ChannelMatrix {
+    fn get_stereo_coefficient(channel: Channels) -> [f32; 2] {
+        match channel {
+            Channels::FRONT_LEFT | Channels::FRONT_LEFT_CENTRE => [1_f32, 0_f32],
+            Channels::FRONT_RIGHT | Channels::FRONT_RIGHT_CENTRE => [0_f32, 1_f32],
+            Channels::FRONT_CENTRE => [FRAC_1_SQRT_2, FRAC_1_SQRT_2],
+            Channels::LFE1 | Channels::LFE2 => [0_f32, 0_f32],
+            Channels::REAR_LEFT
+            | Channels::SIDE_LEFT
+            | Channels::REAR_LEFT_CENTRE
+            | Channels::FRONT_LEFT_WIDE
+            | Channels::FRONT_LEFT_HIGH
+            | Channels::TOP_FRONT_LEFT
+            | Channels::TOP_REAR_LEFT => [FRAC_1_SQRT_2, 0_f32],
+            Channels::REAR_RIGHT
+            | Channels::SIDE_RIGHT
+            | Channels::REAR_RIGHT_CENTRE
+            | Channels::FRONT_RIGHT_WIDE
+            | Channels::FRONT_RIGHT_HIGH
+            | Channels::TOP_FRONT_RIGHT
+            | Channels::TOP_REAR_RIGHT => [0_f32, FRAC_1_SQRT_2],
+            _ => [0.5_f32, 0.5_f32],
+        }
+    }
+
+    pub fn downmix_to_stereo(channels: Channels) -> Self {
+        let coefficient_list = if channels.count() == 1 {
+            vec![[1_f32, 1_f32]]
+        } else {
+            channels
+                .iter()
+                .map(Self::get_stereo_coefficient)
+                .collect::<Vec<[f32; 2]>>()
+        };
+
+        Self {
+            coefficient_matrix: (0..2)
+                .map(|output| {
+                    coefficient_list
+                        .iter()
+                        .map(|coefficient| coefficient[output])
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+
+    pub fn upmix_from_stereo(channels: Channels, upmix_is_enabled: bool) -> Self {
+        let has_front_pair = channels.contains(Channels::FRONT_LEFT | Channels::FRONT_RIGHT);
+
+        Self {
+            coefficient_matrix: channels
+                .iter()
+                .map(|channel| match channel {
+                    Channels::FRONT_LEFT => [1_f32, 0_f32],
+                    Channels::FRONT_RIGHT => [0_f32, 1_f32],
+                    _ if !has_front_pair => [0.5_f32, 0.5_f32],
+                    _ if !upmix_is_enabled => [0_f32, 0_f32],
+                    Channels::FRONT_CENTRE => [0.5_f32, 0.5_f32],
+                    Channels::REAR_LEFT | Channels::SIDE_LEFT => [FRAC_1_SQRT_2, 0_f32],
+                    Channels::REAR_RIGHT | Channels::SIDE_RIGHT => [0_f32, FRAC_1_SQRT_2],
+                    Channels::REAR_CENTRE => [0.5_f32 * FRAC_1_SQRT_2, 0.5_f32 * FRAC_1_SQRT_2],
+                    _ => [0_f32, 0_f32],
+                })
+                .map(|coefficient| coefficient.to_vec())
+                .collect(),
+        }
+    }
+
+    pub fn from_custom(
+        coefficient_matrix: &[Vec<f64>],
+        input_count: usize,
+        output_count: usize,
+    ) -> Option<Self> {
+        (coefficient_matrix.len() == output_count
+            && coefficient_matrix.iter().all(|coefficient_list| {
+                coefficient_list.len() == input_count
+                    && coefficient_list
+                        .iter()
+                        .all(|coefficient| coefficient.is_finite())
+            }))
+        .then(|| Self {
+            coefficient_matrix: coefficient_matrix
+                .iter()
+                .map(|coefficient_list| {
+                    coefficient_list
+                        .iter()
+                        .map(|coefficient| *coefficient as f32)
+                        .collect()
+                })
+                .collect(),
+        })
+    }
+
+    pub fn get_output_count(&self) -> usize {
+        self.coefficient_matrix.len()
+    }
+
+    pub fn map_frame<'a>(&'a self, frame: &'a [f32]) -> impl Iterator<Item = f32> + 'a {
+        self.coefficient_matrix.iter().map(move |coefficient_list| {
+            coefficient_list
+                .iter()
+                .zip(frame)
+                .map(|(coefficient, sample)| coefficient * sample)
+                .sum()
+        })
+    }
+
+    pub fn apply(&self, source: Vec<Vec<f32>>, target: &mut VecDeque<[f32; 2]>) -> u32 {
+        let frame_count = source
+            .iter()
+            .map(|channel| channel.len())
+            .min()
+            .unwrap_or(0);
+
+        target.reserve(frame_count);
+
+        (0..frame_count)
+            .map(|index| {
+                let mut frame = [0_f32; 2];
+
+                frame
+                    .iter_mut()
+                    .zip(self.coefficient_matrix.iter())
+                    .for_each(|(sample, coefficient_list)| {
+                        *sample = coefficient_list
+                            .iter()
+                            .zip(source.iter())
+                            .map(|(coefficient, channel)| coefficient * channel[index])
+                            .sum();
+                    });
+
+                target.push_back(frame);
+            })
+            .count() as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn interleave_f32(channel_count: usize, upmix_is_enabled: bool, frame: [f32; 2]) -> Vec<f32> {
+        let channel_matrix = ChannelMatrix::upmix_from_stereo(
+            resolve_speaker_layout(channel_count, 0),
+            upmix_is_enabled,
+        );
+
+        let mut buffer_slice = vec![0_u8; channel_count * f32::BYTE_LENGTH];
+
+        interleave::<f32>(&mut buffer_slice, [frame], &channel_matrix, None);
+
+        buffer_slice
+            .chunks_exact(f32::BYTE_LENGTH)
+            .map(|sample| f32::from_ne_bytes(sample.try_into().unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn stereo_to_surround_keeps_front_pair_only() {
+        assert_eq!(
+            interleave_f32(6, false, [0.5_f32, -0.25_f32]),
+            [0.5_f32, -0.25_f32, 0_f32, 0_f32, 0_f32, 0_f32]
+        );
+
+        assert_eq!(
+            interleave_f32(8, false, [0.5_f32, -0.25_f32]),
+            [0.5_f32, -0.25_f32, 0_f32, 0_f32, 0_f32, 0_f32, 0_f32, 0_f32]
+        );
+    }
+
+    #[test]
+    fn stereo_to_5_1_upmix() {
+        assert_eq!(
+            interleave_f32(6, true, [0.5_f32, -0.25_f32]),
+            [
+                0.5_f32,
+                -0.25_f32,
+                0.125_f32,
+                0_f32,
+                0.5_f32 * FRAC_1_SQRT_2,
+                -0.25_f32 * FRAC_1_SQRT_2,
+            ]
+        );
+    }
+
+    #[test]
+    fn stereo_to_7_1_upmix() {
+        assert_eq!(
+            interleave_f32(8, true, [0.5_f32, -0.25_f32]),
+            [
+                0.5_f32,
+                -0.25_f32,
+                0.125_f32,
+                0_f32,
+                0.5_f32 * FRAC_1_SQRT_2,
+                -0.25_f32 * FRAC_1_SQRT_2,
+                0.5_f32 * FRAC_1_SQRT_2,
+                -0.25_f32 * FRAC_1_SQRT_2,
+            ]
+        );
+    }
+
+    #[test]
+    fn s24_packs_three_bytes_per_sample() {
+        let channel_matrix = ChannelMatrix::upmix_from_stereo(resolve_speaker_layout(2, 0), false);
+
+        let mut buffer_slice = vec![0xAA_u8; 2 * 2 * i24::BYTE_LENGTH];
+
+        interleave::<i24>(
+            &mut buffer_slice,
+            [[0.5_f32, -0.5_f32]],
+            &channel_matrix,
+            None,
+        );
+
+        let expected_list = [
+            0x40_0000_i32.to_ne_bytes(),
+            (-0x40_0000_i32).to_ne_bytes(),
+            0_i32.to_ne_bytes(),
+            0_i32.to_ne_bytes(),
+        ];
+
+        buffer_slice
+            .chunks_exact(i24::BYTE_LENGTH)
+            .zip(expected_list)
+            .for_each(|(sample, expected)| {
+                if cfg!(target_endian = "little") {
+                    assert_eq!(sample, &expected[..3]);
+                } else {
+                    assert_eq!(sample, &expected[1..]);
+                }
+            });
+    }
+
+    #[test]
+    fn layout_beyond_7_1_keeps_channel_count() {
+        (9..=MAX_CHANNEL_COUNT).for_each(|channel_count| {
+            assert_eq!(
+                resolve_speaker_layout(channel_count, 0).count(),
+                channel_count
+            );
+        });
+    }
+}
@@ -0,0 +1,252 @@
+use crate::dsp::{DspStage, DspStageConfig};
+
+const TRANSITION_DURATION: f64 = 0.02_f64;
+
+const SILENCE_LEVEL: f64 = -120_f64;
+
+#[napi(object)]
+pub struct CompressorPreset {
+    #[napi(ts_type = "'night' | 'gentle'")]
+    pub preset: Option<String>,
+    pub threshold: Option<f64>,
+    pub ratio: Option<f64>,
+    pub knee: Option<f64>,
+    pub attack: Option<f64>,
+    pub release: Option<f64>,
+    pub makeup_gain: Option<f64>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub struct CompressorConfig {
+    threshold: f64,
+    ratio: f64,
+    knee: f64,
+    attack: f64,
+    release: f64,
+    makeup_gain: f64,
+}
+
+impl From<CompressorPreset> for CompressorConfig {
+    fn from(preset: CompressorPreset) -> Self {
+        let (threshold, ratio, knee, attack, release, makeup_gain) = match preset.preset.as_deref()
+        {
+            Some("gentle") => (-20_f64, 2_f64, 6_f64, 20_f64, 300_f64, 4_f64),
+            _ => (-30_f64, 4_f64, 6_f64, 10_f64, 250_f64, 12_f64),
+        };
+
+        let select = |value: Option<f64>, default: f64, min: f64, max: f64| {
+            value
+                .filter(|value| value.is_finite())
+                .map_or(default, |value| value.clamp(min, max))
+        };
+
+        Self {
+            threshold: select(preset.threshold, threshold, -60_f64, 0_f64),
+            ratio: select(preset.ratio, ratio, 1_f64, 20_f64),
+            knee: select(preset.knee, knee, 0_f64, 24_f64),
+            attack: select(preset.attack, attack, 0.1_f64, 200_f64) / 1_000_f64,
+            release: select(preset.release, release, 10_f64, 2_000_f64) / 1_000_f64,
+            makeup_gain: select(preset.makeup_gain, makeup_gain, 0_f64, 24_f64),
+        }
+    }
+}
+
+impl CompressorConfig {
+    fn compute_gain_reduction(&self, level: f64) -> f64 {
+        let overshoot = level - self.threshold;
+
+        let slope = 1_f64 - 1_f64 / self.ratio;
+
+        if overshoot * 2_f64 <= -self.knee {
+            0_f64
+        } else if overshoot * 2_f64 < self.knee {
+            slope * (overshoot + self.knee / 2_f64).powi(2) / (2_f64 * self.knee)
+        } else {
+            slope * overshoot
+        }
+    }
+}
+
+pub struct Compressor {
+    config: Option<CompressorConfig>,
+    sample_rate: u32,
+    gain: f64,
+    max_gain_reduction: f64,
+}
+
+impl Compressor {
+    pub const ID: &'static str = "compressor";
+
+    fn get_coefficient(&self, duration: f64) -> f64 {
+        1_f64 - (-1_f64 / (duration * self.sample_rate as f64)).exp()
+    }
+
+    pub fn new(config: Option<CompressorConfig>, sample_rate: u32) -> Self {
+        Self {
+            config,
+            sample_rate,
+            gain: 0_f64,
+            max_gain_reduction: 0_f64,
+        }
+    }
+}
+
+impl DspStage for Compressor {
+    fn get_id(&self) -> &'static str {
+        Self::ID
+    }
+
+    fn configure(&mut self, config: DspStageConfig) {
+        if let DspStageConfig::Compressor(config) = config {
+            self.config = config;
+        }
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: u32) {
+        self.sample_rate = sample_rate;
+    }
+
+    fn get_is_transparent(&self) -> bool {
+        self.config.is_none() && self.gain == 0_f64
+    }
+
+    fn take_gain_reduction(&mut self) -> f64 {
+        let gain_reduction = self.max_gain_reduction;
+
+        self.max_gain_reduction = 0_f64;
+
+        gain_reduction
+    }
+
+    fn process(&mut self, frame_list: &mut [[f32; 2]]) {
+        let (attack_coefficient, release_coefficient) = match self.config.as_ref() {
+            Some(config) => (
+                self.get_coefficient(config.attack),
+                self.get_coefficient(config.release),
+            ),
+            None => {
+                let coefficient = self.get_coefficient(TRANSITION_DURATION);
+
+                (coefficient, coefficient)
+            }
+        };
+
+        frame_list.iter_mut().for_each(|frame| {
+            let target_gain = self.config.as_ref().map_or(0_f64, |config| {
+                let peak = frame[0].abs().max(frame[1].abs()) as f64;
+
+                let level = if peak > 0_f64 {
+                    (20_f64 * peak.log10()).max(SILENCE_LEVEL)
+                } else {
+                    SILENCE_LEVEL
+                };
+
+                config.makeup_gain - config.compute_gain_reduction(level)
+            });
+
+            let coefficient = if target_gain < self.gain {
+                attack_coefficient
+            } else {
+                release_coefficient
+            };
+
+            self.gain += (target_gain - self.gain) * coefficient;
+
+            if self.config.is_none() && self.gain.abs() < 0.001_f64 {
+                self.gain = 0_f64;
+            }
+
+            if let Some(config) = self.config.as_ref() {
+                self.max_gain_reduction =
+                    self.max_gain_reduction.max(config.makeup_gain - self.gain);
+            }
+
+            let amplitude = 10_f64.powf(self.gain / 20_f64);
+
+            *frame = frame.map(|sample| (sample as f64 * amplitude) as f32);
+        });
+    }
+
+    fn reset(&mut self) {
+        self.gain = self
+            .config
+            .as_ref()
+            .map_or(0_f64, |config| config.makeup_gain);
+
+        self.max_gain_reduction = 0_f64;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_config(preset: &str) -> CompressorConfig {
+        CompressorConfig::from(CompressorPreset {
+            preset: Some(String::from(preset)),
+            threshold: None,
+            ratio: None,
+            knee: None,
+            attack: None,
+            release: None,
+            makeup_gain: None,
+        })
+    }
+
+    #[test]
+    fn gain_reduction_follows_the_static_curve() {
+        let config = create_config("night");
+
+        assert_eq!(config.compute_gain_reduction(-40_f64), 0_f64);
+
+        assert_eq!(config.compute_gain_reduction(-10_f64), 15_f64);
+
+        let knee_reduction = config.compute_gain_reduction(-30_f64);
+
+        assert!(knee_reduction > 0_f64 && knee_reduction < 0.75_f64 * 3_f64);
+    }
+
+    #[test]
+    fn steady_signal_settles_at_the_curve() {
+        let mut compressor = Compressor::new(Some(create_config("night")), 48_000);
+
+        compressor.reset();
+
+        let level = 10_f32.powf(-10_f32 / 20_f32);
+
+        let mut frame_list = vec![[level, -level]; 96_000];
+
+        compressor.process(&mut frame_list);
+
+        let output_level = 20_f32 * frame_list[95_999][0].abs().log10();
+
+        assert!((output_level - (-10_f32 + 12_f32 - 15_f32)).abs() < 0.05_f32);
+
+        assert!((compressor.take_gain_reduction() - 15_f64).abs() < 0.05_f64);
+
+        assert_eq!(compressor.take_gain_reduction(), 0_f64);
+    }
+
+    #[test]
+    fn disabled_stage_fades_out_and_becomes_transparent() {
+        let mut compressor = Compressor::new(None, 48_000);
+
+        assert!(compressor.get_is_transparent());
+
+        compressor.configure(DspStageConfig::Compressor(Some(create_config("gentle"))));
+
+        compressor.reset();
+
+        compressor.configure(DspStageConfig::Compressor(None));
+
+        assert!(!compressor.get_is_transparent());
+
+        let mut frame_list = vec![[0.01_f32, 0.01_f32]; 48_000];
+
+        compressor.process(&mut frame_list);
+
+        assert!(compressor.get_is_transparent());
+
+        assert_eq!(frame_list[47_999], [0.01_f32, 0.01_f32]);
+    }
+}
@@ -0,0 +1,327 @@
+use crate::dsp::{DspStage, DspStageConfig};
+use std::{f64::consts::PI, mem};
+
+const TRANSITION_DURATION: f64 = 0.02_f64;
+
+#[napi(object)]
+pub struct CrossfeedPreset {
+    #[napi(ts_type = "'default' | 'cmoy' | 'jmeier'")]
+    pub preset: Option<String>,
+    pub cutoff: Option<f64>,
+    pub feed_level: Option<f64>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub struct CrossfeedConfig {
+    cutoff: f64,
+    feed_level: f64,
+}
+
+impl From<CrossfeedPreset> for CrossfeedConfig {
+    fn from(preset: CrossfeedPreset) -> Self {
+        let (cutoff, feed_level) = match preset.preset.as_deref() {
+            Some("cmoy") => (700_f64, 6_f64),
+            Some("jmeier") => (650_f64, 9.5_f64),
+            _ => (700_f64, 4.5_f64),
+        };
+
+        Self {
+            cutoff: preset
+                .cutoff
+                .filter(|cutoff| cutoff.is_finite())
+                .map_or(cutoff, |cutoff| cutoff.clamp(300_f64, 2_000_f64)),
+            feed_level: preset
+                .feed_level
+                .filter(|feed_level| feed_level.is_finite())
+                .map_or(feed_level, |feed_level| feed_level.clamp(1_f64, 15_f64)),
+        }
+    }
+}
+
+struct CrossfeedFilter {
+    low_a0: f64,
+    low_b1: f64,
+    high_a0: f64,
+    high_a1: f64,
+    high_b1: f64,
+    low_state: [f64; 2],
+    high_state: [f64; 2],
+    previous_frame: [f64; 2],
+}
+
+impl CrossfeedFilter {
+    fn new(config: &CrossfeedConfig, sample_rate: u32) -> Self {
+        let low_gain_db = config.feed_level * -5_f64 / 6_f64 - 3_f64;
+
+        let high_gain_db = config.feed_level / 6_f64 - 3_f64;
+
+        let low_gain = 10_f64.powf(low_gain_db / 20_f64);
+
+        let high_gain = 1_f64 - 10_f64.powf(high_gain_db / 20_f64);
+
+        let high_cutoff =
+            config.cutoff * 2_f64.powf((low_gain_db - 20_f64 * high_gain.log10()) / 12_f64);
+
+        let low_x = (-2_f64 * PI * config.cutoff / sample_rate as f64).exp();
+
+        let high_x = (-2_f64 * PI * high_cutoff / sample_rate as f64).exp();
+
+        let compensation = 1_f64 / (1_f64 - high_gain + low_gain);
+
+        Self {
+            low_a0: low_gain * (1_f64 - low_x) * compensation,
+            low_b1: low_x,
+            high_a0: (1_f64 - high_gain * (1_f64 - high_x)) * compensation,
+            high_a1: -high_x * compensation,
+            high_b1: high_x,
+            low_state: [0_f64; 2],
+            high_state: [0_f64; 2],
+            previous_frame: [0_f64; 2],
+        }
+    }
+
+    fn process(&mut self, frame: [f64; 2]) -> [f64; 2] {
+        self.low_state = [0, 1]
+            .map(|channel| self.low_a0 * frame[channel] + self.low_b1 * self.low_state[channel]);
+
+        self.high_state = [0, 1].map(|channel| {
+            self.high_a0 * frame[channel]
+                + self.high_a1 * self.previous_frame[channel]
+                + self.high_b1 * self.high_state[channel]
+        });
+
+        self.previous_frame = frame;
+
+        [
+            self.high_state[0] + self.low_state[1],
+            self.high_state[1] + self.low_state[0],
+        ]
+    }
+
+    fn reset(&mut self) {
+        self.low_state = [0_f64; 2];
+
+        self.high_state = [0_f64; 2];
+
+        self.previous_frame = [0_f64; 2];
+    }
+}
+
+pub struct Crossfeed {
+    config: Option<CrossfeedConfig>,
+    sample_rate: u32,
+    filter: Option<CrossfeedFilter>,
+    fading_filter: Option<(Option<CrossfeedFilter>, usize)>,
+    transition_frame_count: usize,
+}
+
+impl Crossfeed {
+    pub const ID: &'static str = "crossfeed";
+
+    fn get_transition_frame_count(sample_rate: u32) -> usize {
+        ((sample_rate as f64 * TRANSITION_DURATION).round() as usize).max(1)
+    }
+
+    fn run_filter(filter: &mut Option<CrossfeedFilter>, frame: [f64; 2]) -> [f64; 2] {
+        match filter.as_mut() {
+            Some(filter) => filter.process(frame),
+            None => frame,
+        }
+    }
+
+    pub fn new(config: Option<CrossfeedConfig>, sample_rate: u32) -> Self {
+        Self {
+            filter: config
+                .as_ref()
+                .map(|config| CrossfeedFilter::new(config, sample_rate)),
+            config,
+            sample_rate,
+            fading_filter: None,
+            transition_frame_count: Self::get_transition_frame_count(sample_rate),
+        }
+    }
+
+    fn set_config(&mut self, config: Option<CrossfeedConfig>) {
+        if config != self.config {
+            let filter = config
+                .as_ref()
+                .map(|config| CrossfeedFilter::new(config, self.sample_rate));
+
+            self.fading_filter = Some((
+                mem::replace(&mut self.filter, filter),
+                self.transition_frame_count,
+            ));
+
+            self.config = config;
+        }
+    }
+}
+
+impl DspStage for Crossfeed {
+    fn get_id(&self) -> &'static str {
+        Self::ID
+    }
+
+    fn configure(&mut self, config: DspStageConfig) {
+        if let DspStageConfig::Crossfeed(config) = config {
+            self.set_config(config);
+        }
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: u32) {
+        if sample_rate != self.sample_rate {
+            self.sample_rate = sample_rate;
+
+            self.filter = self
+                .config
+                .as_ref()
+                .map(|config| CrossfeedFilter::new(config, sample_rate));
+
+            self.fading_filter = None;
+
+            self.transition_frame_count = Self::get_transition_frame_count(sample_rate);
+        }
+    }
+
+    fn get_is_transparent(&self) -> bool {
+        self.fading_filter.is_none() && self.config.is_none()
+    }
+
+    fn process(&mut self, frame_list: &mut [[f32; 2]]) {
+        frame_list.iter_mut().for_each(|frame| {
+            let input = frame.map(|sample| sample as f64);
+
+            let output = Self::run_filter(&mut self.filter, input);
+
+            let output =
+                if let Some((fading_filter, remaining_frame_count)) = self.fading_filter.as_mut() {
+                    let fading_output = Self::run_filter(fading_filter, input);
+
+                    *remaining_frame_count -= 1;
+
+                    let gain = *remaining_frame_count as f64 / self.transition_frame_count as f64;
+
+                    if *remaining_frame_count == 0 {
+                        self.fading_filter = None;
+                    }
+
+                    [
+                        output[0] * (1_f64 - gain) + fading_output[0] * gain,
+                        output[1] * (1_f64 - gain) + fading_output[1] * gain,
+                    ]
+                } else {
+                    output
+                };
+
+            *frame = output.map(|sample| sample as f32);
+        });
+    }
+
+    fn reset(&mut self) {
+        if let Some(filter) = self.filter.as_mut() {
+            filter.reset();
+        }
+
+        self.fading_filter = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_config(preset: Option<&str>) -> CrossfeedConfig {
+        CrossfeedConfig::from(CrossfeedPreset {
+            preset: preset.map(String::from),
+            cutoff: None,
+            feed_level: None,
+        })
+    }
+
+    #[test]
+    fn resolves_presets_and_clamps_overrides() {
+        let config = create_config(Some("jmeier"));
+
+        assert_eq!((config.cutoff, config.feed_level), (650_f64, 9.5_f64));
+
+        let config = CrossfeedConfig::from(CrossfeedPreset {
+            preset: Some(String::from("cmoy")),
+            cutoff: Some(100_f64),
+            feed_level: Some(f64::NAN),
+        });
+
+        assert_eq!((config.cutoff, config.feed_level), (300_f64, 6_f64));
+    }
+
+    #[test]
+    fn disabled_stage_is_transparent() {
+        let mut crossfeed = Crossfeed::new(None, 48_000);
+
+        assert!(crossfeed.get_is_transparent());
+
+        let mut frame_list = [[0.5_f32, -0.25_f32]; 64];
+
+        crossfeed.process(&mut frame_list);
+
+        assert!(
+            frame_list
+                .iter()
+                .all(|frame| *frame == [0.5_f32, -0.25_f32])
+        );
+    }
+
+    #[test]
+    fn identical_channels_keep_their_level_at_low_frequency() {
+        let mut crossfeed = Crossfeed::new(Some(create_config(None)), 48_000);
+
+        let mut frame_list = vec![[0.5_f32, 0.5_f32]; 48_000];
+
+        crossfeed.process(&mut frame_list);
+
+        let [left, right] = frame_list[47_999];
+
+        assert!((left - 0.5_f32).abs() < 1e-4_f32);
+
+        assert!((right - 0.5_f32).abs() < 1e-4_f32);
+    }
+
+    #[test]
+    fn one_sided_input_feeds_the_opposite_channel() {
+        let mut crossfeed = Crossfeed::new(Some(create_config(None)), 48_000);
+
+        let mut frame_list = vec![[1_f32, 0_f32]; 48_000];
+
+        crossfeed.process(&mut frame_list);
+
+        let [left, right] = frame_list[47_999];
+
+        assert!(right > 0.1_f32);
+
+        assert!(left > right);
+
+        assert!((left + right - 1_f32).abs() < 1e-4_f32);
+    }
+
+    #[test]
+    fn disabling_fades_back_to_the_dry_signal() {
+        let mut crossfeed = Crossfeed::new(Some(create_config(None)), 48_000);
+
+        let mut frame_list = vec![[1_f32, 0_f32]; 4_800];
+
+        crossfeed.process(&mut frame_list);
+
+        crossfeed.configure(DspStageConfig::Crossfeed(None));
+
+        assert!(!crossfeed.get_is_transparent());
+
+        let mut frame_list = vec![[1_f32, 0_f32]; 4_800];
+
+        crossfeed.process(&mut frame_list);
+
+        assert!(frame_list[0][1] > 0.1_f32);
+
+        assert_eq!(frame_list[4_799], [1_f32, 0_f32]);
+
+        assert!(crossfeed.get_is_transparent());
+    }
+}
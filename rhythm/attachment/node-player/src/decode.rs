@@ -0,0 +1,412 @@
+#[cfg(feature = "opus")]
+use crate::opus::OpusDecoder;
+use std::{
+    fs::{self, File},
+    io::{ErrorKind as IOErrorKind, Read, Seek},
+    path::Path,
+    sync::{
+        LazyLock,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::UNIX_EPOCH,
+};
+use symphonia::{
+    core::{
+        audio::AudioBufferRef,
+        codecs::{CODEC_TYPE_NULL, CodecParameters, CodecRegistry, Decoder},
+        errors::Error as SymphoniaError,
+        formats::FormatReader,
+        io::MediaSourceStream,
+        meta::{StandardTagKey, Tag, Visual},
+        probe::{Hint, ProbeResult},
+    },
+    default as Symphonia,
+};
+
+static CODEC_REGISTRY: LazyLock<CodecRegistry> = LazyLock::new(|| {
+    let mut codec_registry = CodecRegistry::new();
+
+    Symphonia::register_enabled_codecs(&mut codec_registry);
+
+    #[cfg(feature = "opus")]
+    codec_registry.register_all::<OpusDecoder>();
+
+    codec_registry
+});
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+
+const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+const TAG_KEY_NAMESPACE_LIST: [&str; 2] = ["TXXX:", "com.apple.iTunes:"];
+
+pub enum DecodeException {
+    InvalidFile,
+    IncorrectFile,
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct SourceSignature {
+    path: String,
+    byte_length: u64,
+    modified_time: u128,
+}
+
+impl SourceSignature {
+    pub fn new(path: &str) -> Option<Self> {
+        fs::metadata(path).ok().map(|metadata| Self {
+            path: path.to_string(),
+            byte_length: metadata.len(),
+            modified_time: metadata
+                .modified()
+                .ok()
+                .and_then(|modified_time| modified_time.duration_since(UNIX_EPOCH).ok())
+                .map(|duration| duration.as_nanos())
+                .unwrap_or(0),
+        })
+    }
+
+    pub fn get_digest(&self, variant: u32) -> String {
+        let digest = [
+            self.path.as_bytes(),
+            &self.byte_length.to_le_bytes(),
+            &self.modified_time.to_le_bytes(),
+            &variant.to_le_bytes(),
+        ]
+        .into_iter()
+        .flatten()
+        .fold(FNV_OFFSET_BASIS, |digest, byte| {
+            (digest ^ *byte as u64).wrapping_mul(FNV_PRIME)
+        });
+
+        format!("{:016x}", digest)
+    }
+}
+
+pub struct MediaSource {
+    pub format: Box<dyn FormatReader>,
+    pub track_id: u32,
+    pub codec_params: CodecParameters,
+    pub decoder: Box<dyn Decoder>,
+    pub tag_vector: Vec<Tag>,
+    pub visual_vector: Vec<Visual>,
+}
+
+impl TryFrom<&str> for MediaSource {
+    type Error = DecodeException;
+
+    fn try_from(path: &str) -> Result<Self, Self::Error> {
+        File::open(path)
+            .map_err(|_| DecodeException::InvalidFile)
+            .and_then(|mut file| {
+                let mut hint = Hint::new();
+
+                let mut header = [0_u8; 12];
+
+                let header_length = file.read(&mut header).unwrap_or(0);
+
+                file.rewind().map_err(|_| DecodeException::InvalidFile)?;
+
+                if let Some(extension) =
+                    Self::sniff_extension(&header[..header_length]).or_else(|| {
+                        Path::new(path)
+                            .extension()
+                            .and_then(|extension| extension.to_str())
+                            .map(|extension| extension.to_ascii_lowercase())
+                    })
+                {
+                    hint.with_extension(&extension);
+                }
+
+                Symphonia::get_probe()
+                    .format(
+                        &hint,
+                        MediaSourceStream::new(Box::new(file), Default::default()),
+                        &Default::default(),
+                        &Default::default(),
+                    )
+                    .map_err(|_| DecodeException::InvalidFile)
+            })
+            .and_then(
+                |ProbeResult {
+                     mut format,
+                     mut metadata,
+                 }| {
+                    let (tag_vector, visual_vector) = metadata
+                        .get()
+                        .and_then(|mut metadata| {
+                            metadata.skip_to_latest().map(|revision| {
+                                (revision.tags().to_vec(), revision.visuals().to_vec())
+                            })
+                        })
+                        .into_iter()
+                        .chain(format.metadata().skip_to_latest().map(|revision| {
+                            (revision.tags().to_vec(), revision.visuals().to_vec())
+                        }))
+                        .fold(
+                            (Vec::<Tag>::new(), Vec::<Visual>::new()),
+                            |(mut tag_vector, mut visual_vector), (tag_list, visual_list)| {
+                                tag_vector.extend(tag_list);
+
+                                visual_vector.extend(visual_list);
+
+                                (tag_vector, visual_vector)
+                            },
+                        );
+
+                    format
+                        .tracks()
+                        .iter()
+                        .find(|track| track.codec_params.codec != CODEC_TYPE_NULL)
+                        .and_then(|track| {
+                            CODEC_REGISTRY
+                                .make(&track.codec_params, &Default::default())
+                                .map(|decoder| (track.id, track.codec_params.clone(), decoder))
+                                .ok()
+                        })
+                        .map(|(track_id, codec_params, decoder)| Self {
+                            format,
+                            track_id,
+                            codec_params,
+                            decoder,
+                            tag_vector,
+                            visual_vector,
+                        })
+                        .ok_or(DecodeException::InvalidFile)
+                },
+            )
+    }
+}
+
+impl MediaSource {
+    fn sniff_extension(header: &[u8]) -> Option<String> {
+        match header {
+            [b'f', b'L', b'a', b'C', ..] => Some("flac"),
+            [b'O', b'g', b'g', b'S', ..] => Some("ogg"),
+            [
+                b'R',
+                b'I',
+                b'F',
+                b'F',
+                _,
+                _,
+                _,
+                _,
+                b'W',
+                b'A',
+                b'V',
+                b'E',
+                ..,
+            ] => Some("wav"),
+            [b'F', b'O', b'R', b'M', _, _, _, _, b'A', b'I', b'F', _, ..] => Some("aiff"),
+            [_, _, _, _, b'f', b't', b'y', b'p', ..] => Some("m4a"),
+            [b'I', b'D', b'3', ..] => Some("mp3"),
+            [0xFF, second, ..] if second & 0xF6 == 0xF0 => Some("aac"),
+            [0xFF, second, ..] if second & 0xE0 == 0xE0 => Some("mp3"),
+            _ => None,
+        }
+        .map(String::from)
+    }
+
+    pub fn get_codec_name(&self) -> Option<&'static str> {
+        CODEC_REGISTRY
+            .get_codec(self.codec_params.codec)
+            .map(|codec_descriptor| codec_descriptor.short_name)
+    }
+
+    pub fn find_tag_value(&self, std_key: StandardTagKey, key_list: &[&str]) -> Option<String> {
+        self.tag_vector
+            .iter()
+            .rev()
+            .find(|tag| tag.std_key == Some(std_key))
+            .map(|tag| tag.value.to_string())
+            .or_else(|| self.find_custom_tag_value(key_list))
+    }
+
+    pub fn find_custom_tag_value(&self, key_list: &[&str]) -> Option<String> {
+        self.tag_vector
+            .iter()
+            .rev()
+            .find(|tag| {
+                let key = TAG_KEY_NAMESPACE_LIST
+                    .iter()
+                    .find_map(|namespace| tag.key.strip_prefix(namespace))
+                    .unwrap_or(&tag.key);
+
+                key_list
+                    .iter()
+                    .any(|expected_key| key.eq_ignore_ascii_case(expected_key))
+            })
+            .map(|tag| tag.value.to_string())
+    }
+
+    pub fn find_replay_gain_value(&self, std_key: StandardTagKey, key: &str) -> Option<f64> {
+        self.find_tag_value(std_key, &[key]).and_then(|value| {
+            let value = value.trim().to_ascii_lowercase();
+
+            value
+                .strip_suffix("db")
+                .unwrap_or(&value)
+                .trim()
+                .trim_start_matches('+')
+                .parse::<f64>()
+                .ok()
+                .filter(|value| value.is_finite())
+        })
+    }
+
+    pub fn scan_frame_count(path: &str, is_cancelled: Option<&AtomicBool>) -> Option<u64> {
+        let mut media_source = Self::try_from(path).ok()?;
+
+        let mut frame_count = 0_u64;
+
+        let mut byte_count = 0_u64;
+
+        loop {
+            if is_cancelled.is_some_and(|is_cancelled| is_cancelled.load(Ordering::Relaxed)) {
+                return None;
+            }
+
+            match media_source.format.next_packet() {
+                Ok(packet) => {
+                    if packet.track_id() != media_source.track_id {
+                        continue;
+                    }
+
+                    frame_count += packet.dur;
+
+                    byte_count += packet.buf().len() as u64;
+                }
+                Err(SymphoniaError::IoError(error))
+                    if error.kind() == IOErrorKind::UnexpectedEof
+                        && error.to_string() == "end of stream" =>
+                {
+                    return Some(frame_count).filter(|frame_count| *frame_count > 0);
+                }
+                Err(_) => {
+                    break;
+                }
+            }
+        }
+
+        fs::metadata(path)
+            .ok()
+            .filter(|_| byte_count > 0)
+            .map(|metadata| (frame_count as f64 * metadata.len() as f64 / byte_count as f64) as u64)
+            .filter(|frame_count| *frame_count > 0)
+    }
+
+    pub fn request_audio_buffer(&mut self) -> Result<Option<AudioBufferRef<'_>>, DecodeException> {
+        loop {
+            let packet = match self.format.next_packet() {
+                Ok(packet) => packet,
+                Err(SymphoniaError::IoError(error))
+                    if error.kind() == IOErrorKind::UnexpectedEof
+                        && error.to_string() == "end of stream" =>
+                {
+                    return Ok(None);
+                }
+                Err(_) => {
+                    return Err(DecodeException::IncorrectFile);
+                }
+            };
+
+            if packet.track_id() != self.track_id {
+                continue;
+            }
+
+            match self.decoder.decode(&packet) {
+                Ok(_) => {
+                    break;
+                }
+                Err(SymphoniaError::IoError(_)) | Err(SymphoniaError::DecodeError(_)) => {
+                    continue;
+                }
+                Err(_) => {
+                    return Err(DecodeException::IncorrectFile);
+                }
+            }
+        }
+
+        Ok(Some(self.decoder.last_decoded()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sniff(header: &[u8]) -> Option<String> {
+        MediaSource::sniff_extension(header)
+    }
+
+    #[test]
+    fn sniffs_container_magic() {
+        assert_eq!(sniff(b"fLaC\0\0\0\x22").as_deref(), Some("flac"));
+
+        assert_eq!(sniff(b"OggS\0\x02").as_deref(), Some("ogg"));
+
+        assert_eq!(sniff(b"RIFF\x24\0\0\0WAVE").as_deref(), Some("wav"));
+
+        assert_eq!(sniff(b"FORM\0\0\0\0AIFC").as_deref(), Some("aiff"));
+
+        assert_eq!(sniff(b"\0\0\0\x20ftypM4A ").as_deref(), Some("m4a"));
+
+        assert_eq!(sniff(b"ID3\x04\0").as_deref(), Some("mp3"));
+    }
+
+    #[test]
+    fn sniffs_adts_before_mpeg_audio_sync() {
+        [0xF1_u8, 0xF9_u8].into_iter().for_each(|second| {
+            assert_eq!(sniff(&[0xFF, second, 0x50, 0x80]).as_deref(), Some("aac"));
+        });
+
+        [0xFB_u8, 0xFA_u8, 0xF3_u8, 0xE3_u8]
+            .into_iter()
+            .for_each(|second| {
+                assert_eq!(sniff(&[0xFF, second, 0x90, 0x64]).as_deref(), Some("mp3"));
+            });
+    }
+
+    #[test]
+    fn leaves_unknown_headers_to_the_extension() {
+        assert_eq!(sniff(b""), None);
+
+        assert_eq!(sniff(&[0xFF, 0x00, 0x00]), None);
+
+        assert_eq!(sniff(b"not audio"), None);
+    }
+
+    #[test]
+    fn digest_is_stable_and_varies_with_every_field() {
+        let signature = SourceSignature {
+            path: String::from("C:\\Music\\track.flac"),
+            byte_length: 1_024,
+            modified_time: 1_700_000_000_000_000_000,
+        };
+
+        assert_eq!(signature.get_digest(0), signature.clone().get_digest(0));
+
+        assert_eq!(signature.get_digest(0).len(), 16);
+
+        assert_ne!(signature.get_digest(0), signature.get_digest(1));
+
+        assert_ne!(
+            signature.get_digest(0),
+            SourceSignature {
+                byte_length: 1_025,
+                ..signature.clone()
+            }
+            .get_digest(0)
+        );
+
+        assert_ne!(
+            signature.get_digest(0),
+            SourceSignature {
+                modified_time: 1_700_000_000_000_000_001,
+                ..signature.clone()
+            }
+            .get_digest(0)
+        );
+    }
+}
@@ -33,6 +33,7 @@ struct DeviceInfo {
     label: String,
 }
 
+#[cfg_attr(test, allow(dead_code))]
 #[napi(ts_return_type = "{ id: string, label: string }[]")]
 pub fn request_device_list(
     env: Env,
@@ -0,0 +1,137 @@
+const SHAPING_COEFFICIENT_LIST: [f32; 3] = [1.623, -0.982, 0.109];
+
+#[derive(Clone, Copy, Default, PartialEq)]
+pub enum DitherMode {
+    #[default]
+    None,
+    Triangular,
+    Shaped,
+}
+
+pub struct Ditherer {
+    mode: DitherMode,
+    scale: f32,
+    random_state: u32,
+    error_history_vector: Vec<[f32; 3]>,
+}
+
+impl Ditherer {
+    pub fn new(mode: DitherMode, bit_depth: u32, channel_count: usize) -> Option<Self> {
+        (mode != DitherMode::None).then(|| Self {
+            mode,
+            scale: (1_u32 << (bit_depth - 1)) as f32,
+            random_state: 0x9E37_79B9,
+            error_history_vector: vec![[0_f32; 3]; channel_count],
+        })
+    }
+
+    fn request_random(&mut self) -> f32 {
+        self.random_state ^= self.random_state << 13;
+
+        self.random_state ^= self.random_state >> 17;
+
+        self.random_state ^= self.random_state << 5;
+
+        self.random_state as f32 / u32::MAX as f32 - 0.5_f32
+    }
+
+    pub fn process(&mut self, channel: usize, value: f32) -> f32 {
+        let scaled_value = value * self.scale;
+
+        if scaled_value.fract() == 0_f32 {
+            self.error_history_vector[channel] = [0_f32; 3];
+
+            return value;
+        }
+
+        let noise = self.request_random() + self.request_random();
+
+        let error_history = &mut self.error_history_vector[channel];
+
+        let target = if self.mode == DitherMode::Shaped {
+            scaled_value
+                - error_history
+                    .iter()
+                    .zip(SHAPING_COEFFICIENT_LIST)
+                    .map(|(error, coefficient)| error * coefficient)
+                    .sum::<f32>()
+        } else {
+            scaled_value
+        };
+
+        let quantized_value = (target + noise).round();
+
+        error_history.rotate_right(1);
+
+        error_history[0] = (quantized_value - target).clamp(-2_f32, 2_f32);
+
+        quantized_value.clamp(-self.scale, self.scale - 1_f32) / self.scale
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn none_mode_bypasses_dithering() {
+        assert!(Ditherer::new(DitherMode::None, 16, 2).is_none());
+    }
+
+    #[test]
+    fn bit_transparent_samples_pass_through() {
+        [DitherMode::Triangular, DitherMode::Shaped]
+            .iter()
+            .for_each(|mode| {
+                let mut ditherer = Ditherer::new(*mode, 16, 2).unwrap();
+
+                [-32_768_i32, -1_000, -1, 0, 1, 12_345, 32_767]
+                    .iter()
+                    .for_each(|step| {
+                        let value = *step as f32 / 32_768_f32;
+
+                        assert_eq!(ditherer.process(0, value), value);
+
+                        assert_eq!(ditherer.process(1, value), value);
+                    });
+            });
+    }
+
+    #[test]
+    fn output_lands_on_the_target_grid() {
+        [
+            (DitherMode::Triangular, 16_u32),
+            (DitherMode::Shaped, 24_u32),
+        ]
+        .iter()
+        .for_each(|(mode, bit_depth)| {
+            let scale = (1_u32 << (bit_depth - 1)) as f32;
+
+            let mut ditherer = Ditherer::new(*mode, *bit_depth, 1).unwrap();
+
+            (0..10_000).for_each(|index| {
+                let value = (index as f32 * 0.001_f32).sin() * 0.9_f32;
+
+                let output = ditherer.process(0, value) * scale;
+
+                assert_eq!(output.fract(), 0_f32);
+
+                assert!((output - value * scale).abs() <= 4_f32);
+            });
+        });
+    }
+
+    #[test]
+    fn triangular_dither_preserves_the_mean_below_one_step() {
+        let mut ditherer = Ditherer::new(DitherMode::Triangular, 16, 1).unwrap();
+
+        let value = 1_000.3_f32 / 32_768_f32;
+
+        let mean = (0..100_000)
+            .map(|_| ditherer.process(0, value) as f64 * 32_768_f64)
+            .sum::<f64>()
+            / 100_000_f64;
+
+        assert!((mean - 1_000.3_f64).abs() < 0.02_f64);
+    }
+}
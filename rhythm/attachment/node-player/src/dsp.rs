@@ -0,0 +1,187 @@
+use crate::{
+    compressor::{Compressor, CompressorConfig},
+    crossfeed::{Crossfeed, CrossfeedConfig},
+    equalizer::{Equalizer, EqualizerConfig},
+};
+
+const DEFAULT_SAMPLE_RATE: u32 = 48_000;
+
+#[napi(object)]
+pub struct DspStageArrangement {
+    pub id: String,
+    pub enabled: bool,
+}
+
+pub enum DspStageConfig {
+    Equalizer(Option<EqualizerConfig>),
+    Crossfeed(Option<CrossfeedConfig>),
+    Compressor(Option<CompressorConfig>),
+}
+
+impl DspStageConfig {
+    pub fn get_id(&self) -> &'static str {
+        match self {
+            DspStageConfig::Equalizer(_) => Equalizer::ID,
+            DspStageConfig::Crossfeed(_) => Crossfeed::ID,
+            DspStageConfig::Compressor(_) => Compressor::ID,
+        }
+    }
+}
+
+pub trait DspStage {
+    fn get_id(&self) -> &'static str;
+
+    fn configure(&mut self, config: DspStageConfig);
+
+    fn set_sample_rate(&mut self, sample_rate: u32);
+
+    fn get_latency(&self) -> u32 {
+        0
+    }
+
+    fn get_is_transparent(&self) -> bool {
+        false
+    }
+
+    fn take_gain_reduction(&mut self) -> f64 {
+        0_f64
+    }
+
+    fn process(&mut self, frame_list: &mut [[f32; 2]]);
+
+    fn reset(&mut self);
+}
+
+struct DspStageSlot {
+    stage: Box<dyn DspStage>,
+    is_enabled: bool,
+}
+
+pub struct DspChain {
+    slot_list: Vec<DspStageSlot>,
+    sample_rate: u32,
+    bypass_is_enabled: bool,
+}
+
+impl Default for DspChain {
+    fn default() -> Self {
+        let stage_list: Vec<Box<dyn DspStage>> = vec![
+            Box::new(Equalizer::new(
+                EqualizerConfig::default(),
+                DEFAULT_SAMPLE_RATE,
+            )),
+            Box::new(Crossfeed::new(None, DEFAULT_SAMPLE_RATE)),
+            Box::new(Compressor::new(None, DEFAULT_SAMPLE_RATE)),
+        ];
+
+        Self {
+            slot_list: stage_list
+                .into_iter()
+                .map(|stage| DspStageSlot {
+                    stage,
+                    is_enabled: true,
+                })
+                .collect(),
+            sample_rate: DEFAULT_SAMPLE_RATE,
+            bypass_is_enabled: false,
+        }
+    }
+}
+
+impl DspChain {
+    pub fn set_sample_rate(&mut self, sample_rate: u32) {
+        if sample_rate != self.sample_rate {
+            self.sample_rate = sample_rate;
+
+            self.slot_list
+                .iter_mut()
+                .for_each(|slot| slot.stage.set_sample_rate(sample_rate));
+        }
+    }
+
+    pub fn get_bypass(&self) -> bool {
+        self.bypass_is_enabled
+    }
+
+    pub fn set_bypass(&mut self, bypass_is_enabled: bool) {
+        if bypass_is_enabled != self.bypass_is_enabled {
+            self.bypass_is_enabled = bypass_is_enabled;
+
+            self.reset();
+        }
+    }
+
+    pub fn arrange(&mut self, arrangement: &[(String, bool)]) {
+        let mut slot_list = Vec::with_capacity(self.slot_list.len());
+
+        arrangement.iter().for_each(|(id, is_enabled)| {
+            if let Some(index) = self
+                .slot_list
+                .iter()
+                .position(|slot| slot.stage.get_id() == id)
+            {
+                let mut slot = self.slot_list.remove(index);
+
+                if slot.is_enabled != *is_enabled {
+                    slot.is_enabled = *is_enabled;
+
+                    slot.stage.reset();
+                }
+
+                slot_list.push(slot);
+            }
+        });
+
+        slot_list.append(&mut self.slot_list);
+
+        self.slot_list = slot_list;
+    }
+
+    pub fn configure(&mut self, config: DspStageConfig) {
+        if let Some(slot) = self
+            .slot_list
+            .iter_mut()
+            .find(|slot| slot.stage.get_id() == config.get_id())
+        {
+            slot.stage.configure(config);
+        }
+    }
+
+    fn active_slot_iter_mut(&mut self) -> impl Iterator<Item = &mut DspStageSlot> {
+        let bypass_is_enabled = self.bypass_is_enabled;
+
+        self.slot_list
+            .iter_mut()
+            .filter(move |slot| !bypass_is_enabled && slot.is_enabled)
+    }
+
+    pub fn get_latency(&self) -> u32 {
+        if self.bypass_is_enabled {
+            0
+        } else {
+            self.slot_list
+                .iter()
+                .filter(|slot| slot.is_enabled)
+                .map(|slot| slot.stage.get_latency())
+                .sum()
+        }
+    }
+
+    pub fn take_gain_reduction(&mut self) -> f64 {
+        self.active_slot_iter_mut()
+            .map(|slot| slot.stage.take_gain_reduction())
+            .sum()
+    }
+
+    pub fn process(&mut self, frame_list: &mut [[f32; 2]]) {
+        self.active_slot_iter_mut()
+            .filter(|slot| !slot.stage.get_is_transparent())
+            .for_each(|slot| slot.stage.process(frame_list));
+    }
+
+    pub fn reset(&mut self) {
+        self.slot_list
+            .iter_mut()
+            .for_each(|slot| slot.stage.reset());
+    }
+}
@@ -0,0 +1,366 @@
+use crate::{
+    biquad::{Biquad, FilterKind},
+    dsp::{DspStage, DspStageConfig},
+};
+use std::{f64::consts::FRAC_1_SQRT_2, mem};
+
+const MAX_BAND_COUNT: usize = 64;
+
+const TRANSITION_DURATION: f64 = 0.02_f64;
+
+#[napi(object)]
+pub struct EqualizerBand {
+    #[napi(
+        ts_type = "'peaking' | 'low-shelf' | 'high-shelf' | 'low-pass' | 'high-pass' | 'notch'"
+    )]
+    pub kind: String,
+    pub frequency: f64,
+    pub gain: f64,
+    pub q: f64,
+}
+
+#[napi(object)]
+pub struct EqualizerPreset {
+    pub preamp: f64,
+    pub band_list: Vec<EqualizerBand>,
+}
+
+#[derive(Clone, Default, PartialEq)]
+pub struct EqualizerConfig {
+    preamp: f64,
+    band_list: Vec<(FilterKind, f64, f64, f64)>,
+}
+
+impl From<EqualizerPreset> for EqualizerConfig {
+    fn from(preset: EqualizerPreset) -> Self {
+        Self {
+            preamp: if preset.preamp.is_finite() {
+                preset.preamp
+            } else {
+                0_f64
+            },
+            band_list: preset
+                .band_list
+                .into_iter()
+                .filter_map(|band| {
+                    let kind = match band.kind.as_str() {
+                        "peaking" => FilterKind::Peaking,
+                        "low-shelf" => FilterKind::LowShelf,
+                        "high-shelf" => FilterKind::HighShelf,
+                        "low-pass" => FilterKind::LowPass,
+                        "high-pass" => FilterKind::HighPass,
+                        "notch" => FilterKind::Notch,
+                        _ => return None,
+                    };
+
+                    (band.frequency.is_finite()
+                        && band.frequency > 0_f64
+                        && band.gain.is_finite()
+                        && band.q.is_finite()
+                        && band.q > 0_f64)
+                        .then_some((kind, band.frequency, band.gain, band.q))
+                })
+                .take(MAX_BAND_COUNT)
+                .collect(),
+        }
+    }
+}
+
+impl EqualizerPreset {
+    fn parse_value(token_list: &[&str], key: &str) -> Option<f64> {
+        token_list
+            .iter()
+            .position(|token| token.eq_ignore_ascii_case(key))
+            .and_then(|index| token_list.get(index + 1))
+            .and_then(|value| value.parse::<f64>().ok())
+            .filter(|value| value.is_finite())
+    }
+
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut preamp = None;
+
+        let mut band_list = Vec::new();
+
+        text.lines().for_each(|line| {
+            let line = line.trim();
+
+            if let Some((label, content)) = line.split_once(':') {
+                let token_list = content.split_whitespace().collect::<Vec<&str>>();
+
+                if label.trim().eq_ignore_ascii_case("preamp") {
+                    preamp = token_list
+                        .first()
+                        .and_then(|value| value.parse::<f64>().ok());
+                } else if label
+                    .trim()
+                    .get(..6)
+                    .is_some_and(|prefix| prefix.eq_ignore_ascii_case("filter"))
+                    && token_list
+                        .first()
+                        .is_some_and(|state| state.eq_ignore_ascii_case("on"))
+                    && let Some(kind) = token_list.get(1).and_then(|kind| {
+                        match kind.to_ascii_uppercase().as_str() {
+                            "PK" | "PEQ" | "MODAL" => Some("peaking"),
+                            "LS" | "LSC" | "LSQ" => Some("low-shelf"),
+                            "HS" | "HSC" | "HSQ" => Some("high-shelf"),
+                            "LP" | "LPQ" => Some("low-pass"),
+                            "HP" | "HPQ" => Some("high-pass"),
+                            "NO" => Some("notch"),
+                            _ => None,
+                        }
+                    })
+                    && let Some(frequency) = Self::parse_value(&token_list, "fc")
+                {
+                    band_list.push(EqualizerBand {
+                        kind: String::from(kind),
+                        frequency,
+                        gain: Self::parse_value(&token_list, "gain").unwrap_or(0_f64),
+                        q: Self::parse_value(&token_list, "q").unwrap_or(FRAC_1_SQRT_2),
+                    });
+                }
+            }
+        });
+
+        (preamp.is_some() || !band_list.is_empty()).then(|| Self {
+            preamp: preamp.filter(|preamp| preamp.is_finite()).unwrap_or(0_f64),
+            band_list,
+        })
+    }
+}
+
+#[cfg_attr(test, allow(dead_code))]
+#[napi(ts_return_type = "null | EqualizerPreset")]
+pub fn parse_equalizer_preset(text: String) -> Option<EqualizerPreset> {
+    EqualizerPreset::parse(&text)
+}
+
+struct FilterChain {
+    amplitude: f64,
+    biquad_list: Vec<[Biquad; 2]>,
+}
+
+impl FilterChain {
+    fn new(config: &EqualizerConfig, sample_rate: u32) -> Self {
+        Self {
+            amplitude: 10_f64.powf(config.preamp / 20_f64),
+            biquad_list: config
+                .band_list
+                .iter()
+                .map(|(kind, frequency, gain, q)| {
+                    let biquad = Biquad::design(*kind, *frequency, *gain, *q, sample_rate);
+
+                    [biquad.clone(), biquad]
+                })
+                .collect(),
+        }
+    }
+
+    fn process(&mut self, frame: [f32; 2]) -> [f64; 2] {
+        let mut output = frame.map(|sample| sample as f64 * self.amplitude);
+
+        self.biquad_list.iter_mut().for_each(|biquad_pair| {
+            output
+                .iter_mut()
+                .zip(biquad_pair.iter_mut())
+                .for_each(|(sample, biquad)| {
+                    *sample = biquad.process(*sample);
+                });
+        });
+
+        output
+    }
+
+    fn reset(&mut self) {
+        self.biquad_list
+            .iter_mut()
+            .flatten()
+            .for_each(|biquad| biquad.reset());
+    }
+}
+
+pub struct Equalizer {
+    config: EqualizerConfig,
+    sample_rate: u32,
+    filter_chain: FilterChain,
+    fading_filter_chain: Option<(FilterChain, usize)>,
+    transition_frame_count: usize,
+}
+
+impl Equalizer {
+    pub const ID: &'static str = "equalizer";
+
+    fn get_transition_frame_count(sample_rate: u32) -> usize {
+        ((sample_rate as f64 * TRANSITION_DURATION).round() as usize).max(1)
+    }
+
+    pub fn new(config: EqualizerConfig, sample_rate: u32) -> Self {
+        Self {
+            filter_chain: FilterChain::new(&config, sample_rate),
+            config,
+            sample_rate,
+            fading_filter_chain: None,
+            transition_frame_count: Self::get_transition_frame_count(sample_rate),
+        }
+    }
+
+    fn set_config(&mut self, config: EqualizerConfig) {
+        if config != self.config {
+            let filter_chain = FilterChain::new(&config, self.sample_rate);
+
+            self.fading_filter_chain = Some((
+                mem::replace(&mut self.filter_chain, filter_chain),
+                self.transition_frame_count,
+            ));
+
+            self.config = config;
+        }
+    }
+}
+
+impl DspStage for Equalizer {
+    fn get_id(&self) -> &'static str {
+        Self::ID
+    }
+
+    fn configure(&mut self, config: DspStageConfig) {
+        if let DspStageConfig::Equalizer(config) = config {
+            self.set_config(config.unwrap_or_default());
+        }
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: u32) {
+        if sample_rate != self.sample_rate {
+            self.sample_rate = sample_rate;
+
+            self.filter_chain = FilterChain::new(&self.config, sample_rate);
+
+            self.fading_filter_chain = None;
+
+            self.transition_frame_count = Self::get_transition_frame_count(sample_rate);
+        }
+    }
+
+    fn get_is_transparent(&self) -> bool {
+        self.fading_filter_chain.is_none() && self.config == EqualizerConfig::default()
+    }
+
+    fn process(&mut self, frame_list: &mut [[f32; 2]]) {
+        frame_list.iter_mut().for_each(|frame| {
+            let output = self.filter_chain.process(*frame);
+
+            let output = if let Some((fading_filter_chain, remaining_frame_count)) =
+                self.fading_filter_chain.as_mut()
+            {
+                let fading_output = fading_filter_chain.process(*frame);
+
+                *remaining_frame_count -= 1;
+
+                let gain = *remaining_frame_count as f64 / self.transition_frame_count as f64;
+
+                if *remaining_frame_count == 0 {
+                    self.fading_filter_chain = None;
+                }
+
+                [
+                    output[0] * (1_f64 - gain) + fading_output[0] * gain,
+                    output[1] * (1_f64 - gain) + fading_output[1] * gain,
+                ]
+            } else {
+                output
+            };
+
+            *frame = output.map(|sample| sample as f32);
+        });
+    }
+
+    fn reset(&mut self) {
+        self.filter_chain.reset();
+
+        self.fading_filter_chain = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_equalizer_apo_preset() {
+        let preset = EqualizerPreset::parse(
+            "Preamp: -6.2 dB\r\n\
+            Filter 1: ON LSC Fc 105 Hz Gain 4.5 dB Q 0.70\r\n\
+            Filter 2: ON PK Fc 2100 Hz Gain -3.1 dB Q 1.41\r\n\
+            Filter 3: OFF PK Fc 4000 Hz Gain 2.0 dB Q 2.00\r\n\
+            Filter 4: ON HP Fc 20 Hz\r\n\
+            Filter 5: ON XX Fc 8000 Hz Gain 1.0 dB Q 1.00\r\n",
+        )
+        .unwrap();
+
+        assert_eq!(preset.preamp, -6.2_f64);
+
+        assert_eq!(
+            preset
+                .band_list
+                .iter()
+                .map(|band| (band.kind.as_str(), band.frequency, band.gain, band.q))
+                .collect::<Vec<(&str, f64, f64, f64)>>(),
+            vec![
+                ("low-shelf", 105_f64, 4.5_f64, 0.7_f64),
+                ("peaking", 2_100_f64, -3.1_f64, 1.41_f64),
+                ("high-pass", 20_f64, 0_f64, FRAC_1_SQRT_2),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_text_without_preamp_or_filters() {
+        assert!(EqualizerPreset::parse("").is_none());
+
+        assert!(EqualizerPreset::parse("Filter 1: OFF PK Fc 1000 Hz Gain 3 dB Q 1").is_none());
+    }
+
+    #[test]
+    fn default_config_is_transparent() {
+        let mut equalizer = Equalizer::new(EqualizerConfig::default(), 48_000);
+
+        assert!(equalizer.get_is_transparent());
+
+        let mut frame_list = [[0.5_f32, -0.25_f32]; 64];
+
+        equalizer.process(&mut frame_list);
+
+        assert!(
+            frame_list
+                .iter()
+                .all(|frame| *frame == [0.5_f32, -0.25_f32])
+        );
+    }
+
+    #[test]
+    fn preamp_scales_and_fades_in() {
+        let mut equalizer = Equalizer::new(EqualizerConfig::default(), 48_000);
+
+        equalizer.configure(DspStageConfig::Equalizer(Some(EqualizerConfig::from(
+            EqualizerPreset {
+                preamp: -6.020_599_913_279_624_f64,
+                band_list: Vec::new(),
+            },
+        ))));
+
+        assert!(!equalizer.get_is_transparent());
+
+        let mut frame_list = [[1_f32, 1_f32]; 2_000];
+
+        equalizer.process(&mut frame_list);
+
+        assert!(frame_list[0][0] > 0.99_f32);
+
+        assert!((frame_list[1_999][0] - 0.5_f32).abs() < 1e-6_f32);
+
+        assert!(
+            frame_list
+                .windows(2)
+                .all(|frame_pair| frame_pair[1][0] <= frame_pair[0][0])
+        );
+    }
+}
@@ -3,5 +3,24 @@
 #[macro_use]
 extern crate napi_derive;
 
+mod analysis;
+mod artwork;
+mod biquad;
+mod channel;
+mod compressor;
+mod crossfeed;
+mod decode;
 mod device;
+mod dither;
+mod dsp;
+mod equalizer;
+mod limiter;
+mod loudness;
+mod metadata;
+#[cfg(feature = "opus")]
+mod opus;
 mod player;
+mod ramp;
+mod resample;
+mod stretch;
+mod waveform;
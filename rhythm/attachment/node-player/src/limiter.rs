@@ -0,0 +1,295 @@
+use std::{collections::VecDeque, f64::consts::PI};
+
+const LOOKAHEAD_DURATION: f64 = 0.0015_f64;
+
+const RELEASE_DURATION: f64 = 0.1_f64;
+
+const OVERSAMPLING_FACTOR: usize = 4;
+
+const INTERPOLATION_TAP_COUNT: usize = 8;
+
+const INTERPOLATION_DELAY: usize = INTERPOLATION_TAP_COUNT / 2;
+
+pub struct TruePeakLimiter {
+    ceiling: f64,
+    lookahead_frame_count: usize,
+    release_coefficient: f64,
+    phase_coefficient_list: Vec<[f64; INTERPOLATION_TAP_COUNT]>,
+    history: [[f64; INTERPOLATION_TAP_COUNT]; 2],
+    delay_line: VecDeque<[f32; 2]>,
+    required_gain_window: VecDeque<(u64, f64)>,
+    held_gain_list: VecDeque<f64>,
+    held_gain_sum: f64,
+    frame_index: u64,
+    gain: f64,
+    max_reduction: f64,
+}
+
+impl TruePeakLimiter {
+    pub fn new(ceiling_db: f64, sample_rate: u32) -> Self {
+        let lookahead_frame_count =
+            ((LOOKAHEAD_DURATION * sample_rate as f64).round() as usize).max(1);
+
+        let phase_coefficient_list = (1..OVERSAMPLING_FACTOR)
+            .map(|phase| {
+                let mut coefficient_list = [0_f64; INTERPOLATION_TAP_COUNT];
+
+                coefficient_list
+                    .iter_mut()
+                    .enumerate()
+                    .for_each(|(index, coefficient)| {
+                        let offset = index as f64
+                            - (INTERPOLATION_DELAY - 1) as f64
+                            - phase as f64 / OVERSAMPLING_FACTOR as f64;
+
+                        let sinc = if offset == 0_f64 {
+                            1_f64
+                        } else {
+                            (PI * offset).sin() / (PI * offset)
+                        };
+
+                        let window = 0.5_f64
+                            + 0.5_f64
+                                * (PI * offset / (INTERPOLATION_DELAY as f64 + 0.5_f64)).cos();
+
+                        *coefficient = sinc * window;
+                    });
+
+                coefficient_list
+            })
+            .collect();
+
+        let mut limiter = Self {
+            ceiling: 10_f64.powf(ceiling_db.min(0_f64) / 20_f64),
+            lookahead_frame_count,
+            release_coefficient: 1_f64 - (-1_f64 / (RELEASE_DURATION * sample_rate as f64)).exp(),
+            phase_coefficient_list,
+            history: [[0_f64; INTERPOLATION_TAP_COUNT]; 2],
+            delay_line: VecDeque::new(),
+            required_gain_window: VecDeque::new(),
+            held_gain_list: VecDeque::new(),
+            held_gain_sum: 0_f64,
+            frame_index: 0,
+            gain: 1_f64,
+            max_reduction: 1_f64,
+        };
+
+        limiter.reset();
+
+        limiter
+    }
+
+    pub fn get_latency(&self) -> u32 {
+        (self.lookahead_frame_count + INTERPOLATION_DELAY) as u32
+    }
+
+    pub fn take_gain_reduction(&mut self) -> f64 {
+        let gain_reduction = -20_f64 * self.max_reduction.log10();
+
+        self.max_reduction = self.gain;
+
+        gain_reduction
+    }
+
+    fn estimate_true_peak(&mut self, frame: [f32; 2]) -> f64 {
+        self.history
+            .iter_mut()
+            .zip(frame)
+            .map(|(history, sample)| {
+                history.rotate_left(1);
+
+                history[INTERPOLATION_TAP_COUNT - 1] = sample as f64;
+
+                self.phase_coefficient_list
+                    .iter()
+                    .map(|coefficient_list| {
+                        history
+                            .iter()
+                            .zip(coefficient_list)
+                            .map(|(sample, coefficient)| sample * coefficient)
+                            .sum::<f64>()
+                            .abs()
+                    })
+                    .fold(history[INTERPOLATION_DELAY - 1].abs(), f64::max)
+            })
+            .fold(0_f64, f64::max)
+    }
+
+    pub fn process(&mut self, frame_list: &mut [[f32; 2]]) {
+        frame_list.iter_mut().for_each(|frame| {
+            let true_peak = self.estimate_true_peak(*frame);
+
+            let required_gain = if true_peak > self.ceiling {
+                self.ceiling / true_peak
+            } else {
+                1_f64
+            };
+
+            while self
+                .required_gain_window
+                .back()
+                .is_some_and(|(_, gain)| *gain >= required_gain)
+            {
+                self.required_gain_window.pop_back();
+            }
+
+            self.required_gain_window
+                .push_back((self.frame_index, required_gain));
+
+            while self.required_gain_window.front().is_some_and(|(index, _)| {
+                *index + (self.lookahead_frame_count as u64) + 1 < self.frame_index
+            }) {
+                self.required_gain_window.pop_front();
+            }
+
+            let held_gain = self
+                .required_gain_window
+                .front()
+                .map_or(1_f64, |(_, gain)| *gain);
+
+            self.held_gain_list.push_back(held_gain);
+
+            self.held_gain_sum += held_gain;
+
+            if let Some(expired_gain) = self.held_gain_list.pop_front() {
+                self.held_gain_sum -= expired_gain;
+            }
+
+            let smoothed_gain = self.held_gain_sum / self.lookahead_frame_count as f64;
+
+            self.gain = if smoothed_gain < self.gain {
+                smoothed_gain
+            } else {
+                self.gain + (smoothed_gain - self.gain) * self.release_coefficient
+            }
+            .min(1_f64);
+
+            self.max_reduction = self.max_reduction.min(self.gain);
+
+            self.delay_line.push_back(*frame);
+
+            let delayed_frame = self.delay_line.pop_front().unwrap_or([0_f32; 2]);
+
+            *frame = if self.gain < 1_f64 {
+                delayed_frame.map(|sample| (sample as f64 * self.gain) as f32)
+            } else {
+                delayed_frame
+            };
+
+            self.frame_index += 1;
+        });
+    }
+
+    pub fn reset(&mut self) {
+        self.history = [[0_f64; INTERPOLATION_TAP_COUNT]; 2];
+
+        self.delay_line = VecDeque::from(vec![[0_f32; 2]; self.get_latency() as usize]);
+
+        self.required_gain_window = VecDeque::new();
+
+        self.held_gain_list = VecDeque::from(vec![1_f64; self.lookahead_frame_count]);
+
+        self.held_gain_sum = self.lookahead_frame_count as f64;
+
+        self.frame_index = 0;
+
+        self.gain = 1_f64;
+
+        self.max_reduction = 1_f64;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: u32 = 48_000;
+
+    fn create_sine(amplitude: f32, frequency: f32, frame_count: usize) -> Vec<[f32; 2]> {
+        (0..frame_count)
+            .map(|index| {
+                let sample = amplitude
+                    * (2_f32 * std::f32::consts::PI * frequency * index as f32
+                        / SAMPLE_RATE as f32)
+                        .sin();
+
+                [sample, -sample]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn output_never_exceeds_the_ceiling() {
+        let ceiling = 10_f32.powf(-1_f32 / 20_f32);
+
+        let mut frame_list = create_sine(0.1_f32, 997_f32, 4_800);
+
+        frame_list.extend(create_sine(2_f32, 997_f32, 4_800));
+
+        frame_list.extend(create_sine(0.5_f32, 11_025_f32, 4_800));
+
+        frame_list.extend(create_sine(4_f32, 12_000_f32, 4_800));
+
+        frame_list
+            .iter_mut()
+            .step_by(3_000)
+            .for_each(|frame| *frame = [8_f32, -8_f32]);
+
+        let mut limiter = TruePeakLimiter::new(-1_f64, SAMPLE_RATE);
+
+        frame_list
+            .chunks_mut(480)
+            .for_each(|chunk| limiter.process(chunk));
+
+        assert!(
+            frame_list
+                .iter()
+                .flatten()
+                .all(|sample| sample.abs() <= ceiling * 1.000_1_f32)
+        );
+
+        assert!(limiter.take_gain_reduction() > 18_f64);
+    }
+
+    #[test]
+    fn quiet_signal_is_only_delayed() {
+        let source = create_sine(0.5_f32, 440_f32, 4_800);
+
+        let mut frame_list = source.clone();
+
+        let mut limiter = TruePeakLimiter::new(-1_f64, SAMPLE_RATE);
+
+        limiter.process(&mut frame_list);
+
+        let latency = limiter.get_latency() as usize;
+
+        assert!(
+            frame_list[..latency]
+                .iter()
+                .all(|frame| *frame == [0_f32; 2])
+        );
+
+        assert_eq!(&frame_list[latency..], &source[..source.len() - latency]);
+
+        assert_eq!(limiter.take_gain_reduction(), 0_f64);
+    }
+
+    #[test]
+    fn gain_recovers_after_a_peak() {
+        let mut limiter = TruePeakLimiter::new(-1_f64, SAMPLE_RATE);
+
+        let mut frame_list = create_sine(2_f32, 997_f32, 480);
+
+        limiter.process(&mut frame_list);
+
+        assert!(limiter.take_gain_reduction() > 6_f64);
+
+        let mut frame_list = vec![[0_f32; 2]; SAMPLE_RATE as usize];
+
+        limiter.process(&mut frame_list);
+
+        limiter.take_gain_reduction();
+
+        assert!(limiter.take_gain_reduction() < 0.01_f64);
+    }
+}
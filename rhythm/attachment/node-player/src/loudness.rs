@@ -0,0 +1,668 @@
+use crate::{biquad::Biquad, decode::MediaSource};
+use napi::{
+    JsObject,
+    bindgen_prelude::*,
+    threadsafe_function::{ErrorStrategy, ThreadsafeFunction, ThreadsafeFunctionCallMode},
+};
+use std::{
+    collections::VecDeque,
+    f64::consts::PI,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+};
+use symphonia::core::audio::{Channels, SampleBuffer};
+
+const ABSOLUTE_GATE_LOUDNESS: f64 = -70_f64;
+
+const INTEGRATED_RELATIVE_GATE: f64 = -10_f64;
+
+const RANGE_RELATIVE_GATE: f64 = -20_f64;
+
+const REFERENCE_LOUDNESS: f64 = -18_f64;
+
+const MOMENTARY_SUB_BLOCK_COUNT: usize = 4;
+
+const SHORT_TERM_SUB_BLOCK_COUNT: usize = 30;
+
+const TRUE_PEAK_TAP_COUNT: usize = 12;
+
+struct KWeightingFilter {
+    pre_filter: Biquad,
+    rlb_filter: Biquad,
+}
+
+impl KWeightingFilter {
+    fn new(sample_rate: u32) -> Self {
+        let sample_rate = sample_rate as f64;
+
+        let pre_filter = {
+            let f0 = 1681.974450955533_f64;
+
+            let g = 3.999843853973347_f64;
+
+            let q = 0.7071752369554196_f64;
+
+            let k = (PI * f0 / sample_rate).tan();
+
+            let vh = 10_f64.powf(g / 20_f64);
+
+            let vb = vh.powf(0.4996667741545416_f64);
+
+            let a0 = 1_f64 + k / q + k * k;
+
+            Biquad::new(
+                [
+                    vh + vb * k / q + k * k,
+                    2_f64 * (k * k - vh),
+                    vh - vb * k / q + k * k,
+                ],
+                [a0, 2_f64 * (k * k - 1_f64), 1_f64 - k / q + k * k],
+            )
+        };
+
+        let rlb_filter = {
+            let f0 = 38.13547087602444_f64;
+
+            let q = 0.5003270373238773_f64;
+
+            let k = (PI * f0 / sample_rate).tan();
+
+            Biquad::new(
+                [1_f64, -2_f64, 1_f64],
+                [
+                    1_f64 + k / q + k * k,
+                    2_f64 * (k * k - 1_f64),
+                    1_f64 - k / q + k * k,
+                ],
+            )
+        };
+
+        Self {
+            pre_filter,
+            rlb_filter,
+        }
+    }
+
+    fn process(&mut self, x: f64) -> f64 {
+        self.rlb_filter.process(self.pre_filter.process(x))
+    }
+}
+
+struct TruePeakMeter {
+    factor: usize,
+    coefficient_vector: Vec<f64>,
+    history: VecDeque<f64>,
+}
+
+impl TruePeakMeter {
+    fn new(sample_rate: u32) -> Self {
+        let factor = if sample_rate < 96000 {
+            4
+        } else if sample_rate < 192000 {
+            2
+        } else {
+            1
+        };
+
+        let length = factor * TRUE_PEAK_TAP_COUNT;
+
+        let center = (length - 1) as f64 / 2_f64;
+
+        let coefficient_vector = (0..length)
+            .map(|index| {
+                let x = (index as f64 - center) / factor as f64;
+
+                let sinc = if x == 0_f64 {
+                    1_f64
+                } else {
+                    (PI * x).sin() / (PI * x)
+                };
+
+                let window = 0.5_f64
+                    - 0.5_f64 * (2_f64 * PI * (index as f64 + 0.5_f64) / length as f64).cos();
+
+                sinc * window
+            })
+            .collect();
+
+        Self {
+            factor,
+            coefficient_vector,
+            history: VecDeque::from(vec![0_f64; TRUE_PEAK_TAP_COUNT]),
+        }
+    }
+
+    fn process(&mut self, x: f64) -> f64 {
+        self.history.pop_back();
+
+        self.history.push_front(x);
+
+        if self.factor == 1 {
+            return x.abs();
+        }
+
+        (0..self.factor)
+            .map(|phase| {
+                self.history
+                    .iter()
+                    .enumerate()
+                    .map(|(tap, value)| self.coefficient_vector[tap * self.factor + phase] * value)
+                    .sum::<f64>()
+                    .abs()
+            })
+            .fold(x.abs(), f64::max)
+    }
+}
+
+struct LoudnessMeter {
+    channel_weight_vector: Vec<f64>,
+    filter_vector: Vec<KWeightingFilter>,
+    true_peak_meter_vector: Vec<TruePeakMeter>,
+    sub_block_length: usize,
+    sub_block_position: usize,
+    sub_block_energy_vector: Vec<f64>,
+    sub_block_power_history: VecDeque<f64>,
+    momentary_block_power_vector: Vec<f64>,
+    short_term_block_power_vector: Vec<f64>,
+    sample_peak: f64,
+    true_peak: f64,
+}
+
+impl LoudnessMeter {
+    fn new(sample_rate: u32, channels: Channels) -> Self {
+        let channel_weight_vector = if channels.count() == 0 {
+            vec![1_f64]
+        } else {
+            channels
+                .iter()
+                .map(|channel| match channel {
+                    Channels::LFE1 | Channels::LFE2 => 0_f64,
+                    Channels::REAR_LEFT
+                    | Channels::REAR_RIGHT
+                    | Channels::SIDE_LEFT
+                    | Channels::SIDE_RIGHT => 1.41_f64,
+                    _ => 1_f64,
+                })
+                .collect::<Vec<f64>>()
+        };
+
+        let channel_count = channel_weight_vector.len();
+
+        Self {
+            channel_weight_vector,
+            filter_vector: (0..channel_count)
+                .map(|_| KWeightingFilter::new(sample_rate))
+                .collect(),
+            true_peak_meter_vector: (0..channel_count)
+                .map(|_| TruePeakMeter::new(sample_rate))
+                .collect(),
+            sub_block_length: (sample_rate as usize / 10).max(1),
+            sub_block_position: 0,
+            sub_block_energy_vector: vec![0_f64; channel_count],
+            sub_block_power_history: VecDeque::with_capacity(SHORT_TERM_SUB_BLOCK_COUNT),
+            momentary_block_power_vector: Vec::new(),
+            short_term_block_power_vector: Vec::new(),
+            sample_peak: 0_f64,
+            true_peak: 0_f64,
+        }
+    }
+
+    fn process(&mut self, interleaved_sample_list: &[f32]) {
+        let channel_count = self.channel_weight_vector.len();
+
+        interleaved_sample_list
+            .chunks_exact(channel_count)
+            .for_each(|frame| {
+                frame.iter().enumerate().for_each(|(channel, sample)| {
+                    let sample = *sample as f64;
+
+                    self.sample_peak = self.sample_peak.max(sample.abs());
+
+                    self.true_peak = self
+                        .true_peak
+                        .max(self.true_peak_meter_vector[channel].process(sample));
+
+                    let weighted_sample = self.filter_vector[channel].process(sample);
+
+                    self.sub_block_energy_vector[channel] += weighted_sample * weighted_sample;
+                });
+
+                self.sub_block_position += 1;
+
+                if self.sub_block_position == self.sub_block_length {
+                    self.complete_sub_block();
+                }
+            });
+    }
+
+    fn complete_sub_block(&mut self) {
+        let sub_block_power = self
+            .sub_block_energy_vector
+            .iter()
+            .zip(self.channel_weight_vector.iter())
+            .map(|(energy, weight)| weight * energy / self.sub_block_length as f64)
+            .sum::<f64>();
+
+        self.sub_block_energy_vector.fill(0_f64);
+
+        self.sub_block_position = 0;
+
+        if self.sub_block_power_history.len() == SHORT_TERM_SUB_BLOCK_COUNT {
+            self.sub_block_power_history.pop_front();
+        }
+
+        self.sub_block_power_history.push_back(sub_block_power);
+
+        let history_length = self.sub_block_power_history.len();
+
+        if history_length >= MOMENTARY_SUB_BLOCK_COUNT {
+            self.momentary_block_power_vector.push(
+                self.sub_block_power_history
+                    .iter()
+                    .skip(history_length - MOMENTARY_SUB_BLOCK_COUNT)
+                    .sum::<f64>()
+                    / MOMENTARY_SUB_BLOCK_COUNT as f64,
+            );
+        }
+
+        if history_length == SHORT_TERM_SUB_BLOCK_COUNT {
+            self.short_term_block_power_vector.push(
+                self.sub_block_power_history.iter().sum::<f64>()
+                    / SHORT_TERM_SUB_BLOCK_COUNT as f64,
+            );
+        }
+    }
+
+    fn finish(self) -> TrackMeasurement {
+        TrackMeasurement {
+            momentary_block_power_vector: self.momentary_block_power_vector,
+            short_term_block_power_vector: self.short_term_block_power_vector,
+            sample_peak: self.sample_peak,
+            true_peak: self.true_peak,
+        }
+    }
+}
+
+pub struct TrackMeasurement {
+    momentary_block_power_vector: Vec<f64>,
+    short_term_block_power_vector: Vec<f64>,
+    sample_peak: f64,
+    true_peak: f64,
+}
+
+impl TrackMeasurement {
+    fn measure(path: &str) -> Option<Self> {
+        let mut media_source = MediaSource::try_from(path).ok()?;
+
+        let mut loudness_meter: Option<LoudnessMeter> = None;
+
+        let mut sample_buffer: Option<SampleBuffer<f32>> = None;
+
+        loop {
+            match media_source.request_audio_buffer() {
+                Ok(Some(audio_buffer)) => {
+                    let spec = *audio_buffer.spec();
+
+                    let frame_count = audio_buffer.capacity();
+
+                    if sample_buffer.as_ref().is_none_or(|sample_buffer| {
+                        sample_buffer.capacity() < frame_count * spec.channels.count()
+                    }) {
+                        sample_buffer = Some(SampleBuffer::new(frame_count as u64, spec));
+                    }
+
+                    let loudness_meter = loudness_meter
+                        .get_or_insert_with(|| LoudnessMeter::new(spec.rate, spec.channels));
+
+                    if let Some(sample_buffer) = sample_buffer.as_mut() {
+                        sample_buffer.copy_interleaved_ref(audio_buffer);
+
+                        loudness_meter.process(sample_buffer.samples());
+                    }
+                }
+                Ok(None) => {
+                    break;
+                }
+                Err(_) => {
+                    return None;
+                }
+            }
+        }
+
+        loudness_meter.map(LoudnessMeter::finish)
+    }
+}
+
+struct LoudnessSummary {
+    integrated_loudness: Option<f64>,
+    loudness_range: Option<f64>,
+    sample_peak: f64,
+    true_peak: f64,
+}
+
+impl LoudnessSummary {
+    fn from_measurement_list(measurement_list: &[&TrackMeasurement]) -> Self {
+        let momentary_block_power_vector = measurement_list
+            .iter()
+            .flat_map(|measurement| measurement.momentary_block_power_vector.iter().copied())
+            .collect::<Vec<f64>>();
+
+        let short_term_block_power_vector = measurement_list
+            .iter()
+            .flat_map(|measurement| measurement.short_term_block_power_vector.iter().copied())
+            .collect::<Vec<f64>>();
+
+        Self {
+            integrated_loudness: Self::calculate_integrated_loudness(&momentary_block_power_vector),
+            loudness_range: Self::calculate_loudness_range(&short_term_block_power_vector),
+            sample_peak: measurement_list
+                .iter()
+                .map(|measurement| measurement.sample_peak)
+                .fold(0_f64, f64::max),
+            true_peak: measurement_list
+                .iter()
+                .map(|measurement| measurement.true_peak)
+                .fold(0_f64, f64::max),
+        }
+    }
+
+    fn get_loudness(power: f64) -> f64 {
+        -0.691_f64 + 10_f64 * power.log10()
+    }
+
+    fn get_power(loudness: f64) -> f64 {
+        10_f64.powf((loudness + 0.691_f64) / 10_f64)
+    }
+
+    fn gate(block_power_vector: &[f64], relative_gate: f64) -> Option<Vec<f64>> {
+        let absolute_threshold = Self::get_power(ABSOLUTE_GATE_LOUDNESS);
+
+        let gated_block_power_vector = block_power_vector
+            .iter()
+            .copied()
+            .filter(|power| *power > absolute_threshold)
+            .collect::<Vec<f64>>();
+
+        if gated_block_power_vector.is_empty() {
+            return None;
+        }
+
+        let relative_threshold = gated_block_power_vector.iter().sum::<f64>()
+            / gated_block_power_vector.len() as f64
+            * 10_f64.powf(relative_gate / 10_f64);
+
+        Some(
+            gated_block_power_vector
+                .into_iter()
+                .filter(|power| *power > relative_threshold)
+                .collect::<Vec<f64>>(),
+        )
+        .filter(|gated_block_power_vector| !gated_block_power_vector.is_empty())
+    }
+
+    fn calculate_integrated_loudness(block_power_vector: &[f64]) -> Option<f64> {
+        Self::gate(block_power_vector, INTEGRATED_RELATIVE_GATE).map(|gated_block_power_vector| {
+            Self::get_loudness(
+                gated_block_power_vector.iter().sum::<f64>()
+                    / gated_block_power_vector.len() as f64,
+            )
+        })
+    }
+
+    fn calculate_loudness_range(block_power_vector: &[f64]) -> Option<f64> {
+        Self::gate(block_power_vector, RANGE_RELATIVE_GATE).map(|gated_block_power_vector| {
+            let mut loudness_vector = gated_block_power_vector
+                .into_iter()
+                .map(Self::get_loudness)
+                .collect::<Vec<f64>>();
+
+            loudness_vector.sort_by(f64::total_cmp);
+
+            let last_index = (loudness_vector.len() - 1) as f64;
+
+            loudness_vector[(last_index * 0.95_f64).round() as usize]
+                - loudness_vector[(last_index * 0.1_f64).round() as usize]
+        })
+    }
+
+    fn into_object(self, env: &Env) -> Result<JsObject> {
+        let mut summary = env.create_object()?;
+
+        summary.set("integratedLoudness", self.integrated_loudness)?;
+
+        summary.set("loudnessRange", self.loudness_range)?;
+
+        summary.set("samplePeak", self.sample_peak)?;
+
+        summary.set("truePeak", self.true_peak)?;
+
+        summary.set(
+            "gain",
+            self.integrated_loudness
+                .map(|integrated_loudness| REFERENCE_LOUDNESS - integrated_loudness),
+        )?;
+
+        Ok(summary)
+    }
+}
+
+pub struct LoudnessTask {
+    path_vector: Vec<String>,
+    progress_handler: Option<ThreadsafeFunction<(u32, u32, String), ErrorStrategy::CalleeHandled>>,
+}
+
+impl Task for LoudnessTask {
+    type Output = Vec<Option<TrackMeasurement>>;
+    type JsValue = JsObject;
+
+    fn compute(&mut self) -> Result<Self::Output> {
+        let path_count = self.path_vector.len();
+
+        let next_index = AtomicUsize::new(0);
+
+        let completed_count = AtomicUsize::new(0);
+
+        let worker_count = thread::available_parallelism()
+            .map(|worker_count| worker_count.get())
+            .unwrap_or(1)
+            .min(path_count)
+            .max(1);
+
+        let mut measurement_vector = thread::scope(|scope| {
+            (0..worker_count)
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut measurement_vector = Vec::new();
+
+                        loop {
+                            let index = next_index.fetch_add(1, Ordering::Relaxed);
+
+                            let Some(path) = self.path_vector.get(index) else {
+                                break;
+                            };
+
+                            measurement_vector.push((index, TrackMeasurement::measure(path)));
+
+                            let completed_count =
+                                completed_count.fetch_add(1, Ordering::Relaxed) + 1;
+
+                            if let Some(progress_handler) = self.progress_handler.as_ref() {
+                                progress_handler.call(
+                                    Ok((completed_count as u32, path_count as u32, path.clone())),
+                                    ThreadsafeFunctionCallMode::NonBlocking,
+                                );
+                            }
+                        }
+
+                        measurement_vector
+                    })
+                })
+                .collect::<Vec<_>>()
+                .into_iter()
+                .flat_map(|worker| worker.join().unwrap_or_default())
+                .collect::<Vec<(usize, Option<TrackMeasurement>)>>()
+        });
+
+        measurement_vector.sort_by_key(|(index, _)| *index);
+
+        Ok(measurement_vector
+            .into_iter()
+            .map(|(_, measurement)| measurement)
+            .collect())
+    }
+
+    fn resolve(&mut self, env: Env, output: Self::Output) -> Result<Self::JsValue> {
+        let track_list = output
+            .iter()
+            .map(|measurement| {
+                measurement
+                    .as_ref()
+                    .map(|measurement| {
+                        LoudnessSummary::from_measurement_list(&[measurement]).into_object(&env)
+                    })
+                    .transpose()
+            })
+            .collect::<Result<Vec<Option<JsObject>>>>()?;
+
+        let album_measurement_list = output.iter().flatten().collect::<Vec<&TrackMeasurement>>();
+
+        let mut result = env.create_object()?;
+
+        result.set("trackList", track_list)?;
+
+        result.set(
+            "album",
+            if album_measurement_list.is_empty() {
+                None
+            } else {
+                Some(
+                    LoudnessSummary::from_measurement_list(&album_measurement_list)
+                        .into_object(&env)?,
+                )
+            },
+        )?;
+
+        Ok(result)
+    }
+
+    fn finally(&mut self, _: Env) -> Result<()> {
+        self.progress_handler = None;
+
+        Ok(())
+    }
+}
+
+#[cfg_attr(test, allow(dead_code))]
+#[napi(
+    ts_args_type = "path_list: string[], \
+    progress_handler?: (error: null | Error, completed: number, total: number, path: string) => void",
+    ts_return_type = "Promise<{ \
+    trackList: (null | { integratedLoudness: null | number, loudnessRange: null | number, samplePeak: number, truePeak: number, gain: null | number })[], \
+    album: null | { integratedLoudness: null | number, loudnessRange: null | number, samplePeak: number, truePeak: number, gain: null | number } \
+    }>"
+)]
+pub fn analyze_loudness(
+    path_list: Vec<String>,
+    progress_handler: Option<JsFunction>,
+) -> Result<AsyncTask<LoudnessTask>> {
+    let progress_handler = progress_handler
+        .map(|progress_handler| {
+            progress_handler.create_threadsafe_function(
+                0,
+                |ctx: napi::threadsafe_function::ThreadSafeCallContext<(u32, u32, String)>| {
+                    let (completed_count, path_count, path) = ctx.value;
+
+                    Ok(vec![
+                        ctx.env.create_uint32(completed_count)?.into_unknown(),
+                        ctx.env.create_uint32(path_count)?.into_unknown(),
+                        ctx.env.create_string_from_std(path)?.into_unknown(),
+                    ])
+                },
+            )
+        })
+        .transpose()?;
+
+    Ok(AsyncTask::new(LoudnessTask {
+        path_vector: path_list,
+        progress_handler,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: u32 = 48_000;
+
+    fn measure_stereo_sine(segment_list: &[(f64, f64)]) -> LoudnessSummary {
+        let mut loudness_meter =
+            LoudnessMeter::new(SAMPLE_RATE, Channels::FRONT_LEFT | Channels::FRONT_RIGHT);
+
+        let mut frame_index = 0_usize;
+
+        segment_list.iter().for_each(|(level, duration)| {
+            let amplitude = 10_f64.powf(level / 20_f64);
+
+            let sample_list = (0..(duration * SAMPLE_RATE as f64) as usize)
+                .flat_map(|_| {
+                    let sample = (amplitude
+                        * (2_f64 * PI * 1_000_f64 * frame_index as f64 / SAMPLE_RATE as f64).sin())
+                        as f32;
+
+                    frame_index += 1;
+
+                    [sample, sample]
+                })
+                .collect::<Vec<f32>>();
+
+            loudness_meter.process(&sample_list);
+        });
+
+        LoudnessSummary::from_measurement_list(&[&loudness_meter.finish()])
+    }
+
+    #[test]
+    fn integrated_loudness_matches_tech_3341_sine_cases() {
+        [(-23_f64, -23_f64), (-33_f64, -33_f64)]
+            .into_iter()
+            .for_each(|(level, expected_loudness)| {
+                let integrated_loudness = measure_stereo_sine(&[(level, 5_f64)])
+                    .integrated_loudness
+                    .unwrap();
+
+                assert!(
+                    (integrated_loudness - expected_loudness).abs() <= 0.1_f64,
+                    "{integrated_loudness}"
+                );
+            });
+    }
+
+    #[test]
+    fn integrated_loudness_gates_out_silence() {
+        assert!(
+            measure_stereo_sine(&[(-120_f64, 5_f64)])
+                .integrated_loudness
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn loudness_range_matches_tech_3342_step_case() {
+        let loudness_range = measure_stereo_sine(&[(-20_f64, 10_f64), (-30_f64, 10_f64)])
+            .loudness_range
+            .unwrap();
+
+        assert!((loudness_range - 10_f64).abs() <= 1_f64, "{loudness_range}");
+    }
+
+    #[test]
+    fn true_peak_finds_inter_sample_peak() {
+        let mut true_peak_meter = TruePeakMeter::new(SAMPLE_RATE);
+
+        let true_peak = (0..SAMPLE_RATE as usize / 10)
+            .map(|index| true_peak_meter.process((PI / 2_f64 * index as f64 + PI / 4_f64).sin()))
+            .fold(0_f64, f64::max);
+
+        assert!((20_f64 * true_peak.log10()).abs() <= 0.5_f64, "{true_peak}");
+    }
+}
@@ -0,0 +1,281 @@
+use crate::decode::MediaSource;
+use napi::{JsObject, bindgen_prelude::*};
+use std::fs;
+use symphonia::core::{
+    meta::{StandardTagKey, StandardVisualKey},
+    units::{Time, TimeBase},
+};
+
+pub struct PictureInfo {
+    media_type: String,
+    usage: Option<StandardVisualKey>,
+    width: Option<u32>,
+    height: Option<u32>,
+    byte_length: u32,
+}
+
+pub struct TrackMetadata {
+    title: Option<String>,
+    artist: Option<String>,
+    album: Option<String>,
+    album_artist: Option<String>,
+    track_number: Option<u32>,
+    track_total: Option<u32>,
+    disc_number: Option<u32>,
+    disc_total: Option<u32>,
+    genre: Option<String>,
+    date: Option<String>,
+    lyrics: Option<String>,
+    track_gain: Option<f64>,
+    track_peak: Option<f64>,
+    album_gain: Option<f64>,
+    album_peak: Option<f64>,
+    codec: Option<String>,
+    bit_depth: Option<u32>,
+    sample_rate: Option<u32>,
+    channel_count: Option<u32>,
+    bitrate: Option<u32>,
+    duration: Option<f64>,
+    picture_list: Vec<PictureInfo>,
+}
+
+impl TrackMetadata {
+    fn parse_position(value: Option<String>) -> (Option<u32>, Option<u32>) {
+        value
+            .map(|value| {
+                let mut part_iter = value.split('/').map(|part| part.trim().parse::<u32>().ok());
+
+                (part_iter.next().flatten(), part_iter.next().flatten())
+            })
+            .unwrap_or((None, None))
+    }
+
+    fn read(path: &str) -> Option<Self> {
+        let media_source = MediaSource::try_from(path).ok()?;
+
+        let codec_params = &media_source.codec_params;
+
+        let duration = codec_params
+            .sample_rate
+            .and_then(|sample_rate| {
+                let time_base = codec_params
+                    .time_base
+                    .unwrap_or(TimeBase::new(1, sample_rate));
+
+                codec_params
+                    .n_frames
+                    .or_else(|| MediaSource::scan_frame_count(path, None))
+                    .map(|frame_count| time_base.calc_time(frame_count))
+            })
+            .map(|Time { seconds, frac }| seconds as f64 + frac)
+            .filter(|duration| *duration > 0_f64);
+
+        let (track_number, position_track_total) = Self::parse_position(
+            media_source.find_tag_value(StandardTagKey::TrackNumber, &["TRACKNUMBER", "TRACK"]),
+        );
+
+        let (disc_number, position_disc_total) = Self::parse_position(
+            media_source.find_tag_value(StandardTagKey::DiscNumber, &["DISCNUMBER", "DISC"]),
+        );
+
+        Some(Self {
+            title: media_source.find_tag_value(StandardTagKey::TrackTitle, &["TITLE"]),
+            artist: media_source.find_tag_value(StandardTagKey::Artist, &["ARTIST"]),
+            album: media_source.find_tag_value(StandardTagKey::Album, &["ALBUM"]),
+            album_artist: media_source.find_tag_value(
+                StandardTagKey::AlbumArtist,
+                &["ALBUMARTIST", "ALBUM ARTIST", "ALBUM_ARTIST"],
+            ),
+            track_number,
+            track_total: media_source
+                .find_tag_value(StandardTagKey::TrackTotal, &["TRACKTOTAL", "TOTALTRACKS"])
+                .and_then(|value| value.trim().parse::<u32>().ok())
+                .or(position_track_total),
+            disc_number,
+            disc_total: media_source
+                .find_tag_value(StandardTagKey::DiscTotal, &["DISCTOTAL", "TOTALDISCS"])
+                .and_then(|value| value.trim().parse::<u32>().ok())
+                .or(position_disc_total),
+            genre: media_source.find_tag_value(StandardTagKey::Genre, &["GENRE"]),
+            date: media_source
+                .find_tag_value(StandardTagKey::Date, &["DATE"])
+                .or_else(|| media_source.find_tag_value(StandardTagKey::ReleaseDate, &["YEAR"]))
+                .or_else(|| {
+                    media_source.find_tag_value(
+                        StandardTagKey::OriginalDate,
+                        &["ORIGINALDATE", "ORIGINALYEAR"],
+                    )
+                }),
+            lyrics: media_source
+                .find_tag_value(StandardTagKey::Lyrics, &["LYRICS", "UNSYNCEDLYRICS"]),
+            track_gain: media_source.find_replay_gain_value(
+                StandardTagKey::ReplayGainTrackGain,
+                "REPLAYGAIN_TRACK_GAIN",
+            ),
+            track_peak: media_source.find_replay_gain_value(
+                StandardTagKey::ReplayGainTrackPeak,
+                "REPLAYGAIN_TRACK_PEAK",
+            ),
+            album_gain: media_source.find_replay_gain_value(
+                StandardTagKey::ReplayGainAlbumGain,
+                "REPLAYGAIN_ALBUM_GAIN",
+            ),
+            album_peak: media_source.find_replay_gain_value(
+                StandardTagKey::ReplayGainAlbumPeak,
+                "REPLAYGAIN_ALBUM_PEAK",
+            ),
+            codec: media_source.get_codec_name().map(String::from),
+            bit_depth: codec_params
+                .bits_per_sample
+                .or(codec_params.bits_per_coded_sample),
+            sample_rate: codec_params.sample_rate,
+            channel_count: codec_params
+                .channels
+                .map(|channels| channels.count() as u32),
+            bitrate: duration.and_then(|duration| {
+                fs::metadata(path).ok().map(|metadata| {
+                    let picture_byte_length = media_source
+                        .visual_vector
+                        .iter()
+                        .map(|visual| visual.data.len() as u64)
+                        .sum::<u64>();
+
+                    (metadata.len().saturating_sub(picture_byte_length) as f64 * 8_f64 / duration)
+                        .round() as u32
+                })
+            }),
+            duration,
+            picture_list: media_source
+                .visual_vector
+                .iter()
+                .map(|visual| PictureInfo {
+                    media_type: visual.media_type.clone(),
+                    usage: visual.usage,
+                    width: visual.dimensions.map(|dimensions| dimensions.width),
+                    height: visual.dimensions.map(|dimensions| dimensions.height),
+                    byte_length: visual.data.len() as u32,
+                })
+                .collect(),
+        })
+    }
+
+    fn into_object(self, env: &Env) -> Result<JsObject> {
+        let mut metadata = env.create_object()?;
+
+        metadata.set("title", self.title)?;
+
+        metadata.set("artist", self.artist)?;
+
+        metadata.set("album", self.album)?;
+
+        metadata.set("albumArtist", self.album_artist)?;
+
+        metadata.set("trackNumber", self.track_number)?;
+
+        metadata.set("trackTotal", self.track_total)?;
+
+        metadata.set("discNumber", self.disc_number)?;
+
+        metadata.set("discTotal", self.disc_total)?;
+
+        metadata.set("genre", self.genre)?;
+
+        metadata.set("date", self.date)?;
+
+        metadata.set("lyrics", self.lyrics)?;
+
+        let mut replay_gain = env.create_object()?;
+
+        replay_gain.set("trackGain", self.track_gain)?;
+
+        replay_gain.set("trackPeak", self.track_peak)?;
+
+        replay_gain.set("albumGain", self.album_gain)?;
+
+        replay_gain.set("albumPeak", self.album_peak)?;
+
+        metadata.set("replayGain", replay_gain)?;
+
+        metadata.set("codec", self.codec)?;
+
+        metadata.set("bitDepth", self.bit_depth)?;
+
+        metadata.set("sampleRate", self.sample_rate)?;
+
+        metadata.set("channelCount", self.channel_count)?;
+
+        metadata.set("bitrate", self.bitrate)?;
+
+        metadata.set("duration", self.duration)?;
+
+        metadata.set(
+            "pictureList",
+            self.picture_list
+                .into_iter()
+                .map(|picture_info| {
+                    let mut picture = env.create_object()?;
+
+                    picture.set("mediaType", picture_info.media_type)?;
+
+                    picture.set(
+                        "usage",
+                        picture_info.usage.map(|usage| match usage {
+                            StandardVisualKey::FrontCover => "front-cover",
+                            StandardVisualKey::BackCover => "back-cover",
+                            StandardVisualKey::Leaflet => "leaflet",
+                            StandardVisualKey::Media => "media",
+                            StandardVisualKey::FileIcon | StandardVisualKey::OtherIcon => "icon",
+                            StandardVisualKey::LeadArtistPerformerSoloist
+                            | StandardVisualKey::ArtistPerformer
+                            | StandardVisualKey::BandOrchestra => "artist",
+                            _ => "other",
+                        }),
+                    )?;
+
+                    picture.set("width", picture_info.width)?;
+
+                    picture.set("height", picture_info.height)?;
+
+                    picture.set("byteLength", picture_info.byte_length)?;
+
+                    Ok(picture)
+                })
+                .collect::<Result<Vec<JsObject>>>()?,
+        )?;
+
+        Ok(metadata)
+    }
+}
+
+pub struct MetadataTask {
+    path: String,
+}
+
+impl Task for MetadataTask {
+    type Output = Option<TrackMetadata>;
+    type JsValue = Option<JsObject>;
+
+    fn compute(&mut self) -> Result<Self::Output> {
+        Ok(TrackMetadata::read(&self.path))
+    }
+
+    fn resolve(&mut self, env: Env, output: Self::Output) -> Result<Self::JsValue> {
+        output
+            .map(|track_metadata| track_metadata.into_object(&env))
+            .transpose()
+    }
+}
+
+#[cfg_attr(test, allow(dead_code))]
+#[napi(ts_return_type = "Promise<null | { \
+    title: null | string, artist: null | string, album: null | string, albumArtist: null | string, \
+    trackNumber: null | number, trackTotal: null | number, discNumber: null | number, discTotal: null | number, \
+    genre: null | string, date: null | string, lyrics: null | string, \
+    replayGain: { trackGain: null | number, trackPeak: null | number, albumGain: null | number, albumPeak: null | number }, \
+    codec: null | string, bitDepth: null | number, sampleRate: null | number, channelCount: null | number, \
+    bitrate: null | number, duration: null | number, \
+    pictureList: { mediaType: string, usage: null | 'front-cover' | 'back-cover' | 'leaflet' | 'media' | 'icon' | 'artist' | 'other', width: null | number, height: null | number, byteLength: number }[] \
+    }>")]
+pub fn read_metadata(path: String) -> AsyncTask<MetadataTask> {
+    AsyncTask::new(MetadataTask { path })
+}
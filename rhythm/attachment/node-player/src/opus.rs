@@ -0,0 +1,134 @@
+use audiopus::{
+    Channels as OpusChannels, MutSignals, SampleRate, coder::Decoder as LibOpusDecoder,
+    packet::Packet as OpusPacket,
+};
+use std::{cmp::min, sync::Mutex};
+use symphonia::core::{
+    audio::{AsAudioBufferRef, AudioBuffer, AudioBufferRef, Signal, SignalSpec},
+    codecs::{
+        CODEC_TYPE_OPUS, CodecDescriptor, CodecParameters, Decoder, DecoderOptions, FinalizeResult,
+    },
+    errors::{Result, decode_error, unsupported_error},
+    formats::Packet,
+    support_codec,
+};
+
+const OPUS_SAMPLE_RATE: u32 = 48_000;
+
+const MAX_FRAME_COUNT: usize = 5_760;
+
+pub struct OpusDecoder {
+    decoder: Mutex<LibOpusDecoder>,
+    codec_params: CodecParameters,
+    opus_channels: OpusChannels,
+    pre_skip_frame_count: usize,
+    pre_skip_count: usize,
+    pre_skip_is_pending: bool,
+    sample_vector: Vec<f32>,
+    audio_buffer: AudioBuffer<f32>,
+}
+
+impl Decoder for OpusDecoder {
+    fn try_new(codec_params: &CodecParameters, _: &DecoderOptions) -> Result<Self> {
+        let Some(channels) = codec_params.channels else {
+            return unsupported_error("opus: missing channel layout");
+        };
+
+        let opus_channels = match channels.count() {
+            1 => OpusChannels::Mono,
+            2 => OpusChannels::Stereo,
+            _ => {
+                return unsupported_error("opus: multistream channel layout");
+            }
+        };
+
+        let Ok(decoder) = LibOpusDecoder::new(SampleRate::Hz48000, opus_channels) else {
+            return unsupported_error("opus: failed to create decoder");
+        };
+
+        Ok(Self {
+            decoder: Mutex::new(decoder),
+            codec_params: codec_params.clone(),
+            opus_channels,
+            pre_skip_frame_count: codec_params.delay.unwrap_or(0) as usize,
+            pre_skip_count: codec_params.delay.unwrap_or(0) as usize,
+            pre_skip_is_pending: false,
+            sample_vector: vec![0_f32; MAX_FRAME_COUNT * channels.count()],
+            audio_buffer: AudioBuffer::new(
+                MAX_FRAME_COUNT as u64,
+                SignalSpec::new(OPUS_SAMPLE_RATE, channels),
+            ),
+        })
+    }
+
+    fn supported_codecs() -> &'static [CodecDescriptor] {
+        &[support_codec!(CODEC_TYPE_OPUS, "opus", "Opus")]
+    }
+
+    fn reset(&mut self) {
+        if let Ok(decoder) = LibOpusDecoder::new(SampleRate::Hz48000, self.opus_channels) {
+            self.decoder = Mutex::new(decoder);
+        }
+
+        self.pre_skip_count = 0;
+
+        self.pre_skip_is_pending = true;
+    }
+
+    fn codec_params(&self) -> &CodecParameters {
+        &self.codec_params
+    }
+
+    fn decode(&mut self, packet: &Packet) -> Result<AudioBufferRef<'_>> {
+        let channel_count = self.opus_channels as usize;
+
+        if self.pre_skip_is_pending {
+            self.pre_skip_is_pending = false;
+
+            if packet.ts() == 0 {
+                self.pre_skip_count = self.pre_skip_frame_count;
+            }
+        }
+
+        let Ok(frame_count) = self.decoder.get_mut().map_err(|_| ()).and_then(|decoder| {
+            OpusPacket::try_from(packet.buf())
+                .and_then(|opus_packet| {
+                    MutSignals::try_from(&mut self.sample_vector[..])
+                        .and_then(|signals| decoder.decode_float(Some(opus_packet), signals, false))
+                })
+                .map_err(|_| ())
+        }) else {
+            return decode_error("opus: invalid packet");
+        };
+
+        let skipped_frame_count = min(self.pre_skip_count, frame_count);
+
+        self.pre_skip_count -= skipped_frame_count;
+
+        self.audio_buffer.clear();
+
+        self.audio_buffer
+            .render_reserved(Some(frame_count - skipped_frame_count));
+
+        (0..channel_count).for_each(|channel| {
+            self.audio_buffer
+                .chan_mut(channel)
+                .iter_mut()
+                .enumerate()
+                .for_each(|(index, sample)| {
+                    *sample =
+                        self.sample_vector[(skipped_frame_count + index) * channel_count + channel];
+                });
+        });
+
+        Ok(self.audio_buffer.as_audio_buffer_ref())
+    }
+
+    fn finalize(&mut self) -> FinalizeResult {
+        Default::default()
+    }
+
+    fn last_decoded(&self) -> AudioBufferRef<'_> {
+        self.audio_buffer.as_audio_buffer_ref()
+    }
+}
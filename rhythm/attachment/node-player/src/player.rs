@@ -1,36 +1,46 @@
+use crate::{
+    analysis::{AnalysisConfig, AnalysisFrame, AnalysisTap},
+    channel::{
+        ChannelMatrix, MAX_CHANNEL_COUNT, PackedSample, StereoImage, StereoImageOption, interleave,
+        resolve_speaker_layout,
+    },
+    compressor::{CompressorConfig, CompressorPreset},
+    crossfeed::{CrossfeedConfig, CrossfeedPreset},
+    decode::MediaSource,
+    dither::{DitherMode, Ditherer},
+    dsp::{DspChain, DspStageArrangement, DspStageConfig},
+    equalizer::{EqualizerConfig, EqualizerPreset},
+    limiter::TruePeakLimiter,
+    ramp::GainRamp,
+    resample::{ResampleQuality, SourceResampler},
+    stretch::TimeStretcher,
+};
 use napi::{
+    JsUnknown, NapiRaw,
     bindgen_prelude::*,
     threadsafe_function::{ErrorStrategy, ThreadsafeFunction, ThreadsafeFunctionCallMode},
 };
-use rubato::{
-    Resampler, SincFixedIn, SincInterpolationParameters, SincInterpolationType, WindowFunction,
-};
 use std::{
-    cmp::min,
-    collections::{HashSet, VecDeque},
-    fs::File,
-    io::ErrorKind as IOErrorKind,
-    ops::Deref,
+    collections::{HashMap, HashSet, VecDeque},
+    mem,
+    ops::{Deref, RangeInclusive},
     result::Result,
     slice,
-    sync::mpsc,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        mpsc,
+    },
     thread,
     time::Duration,
-    vec::IntoIter,
 };
-use symphonia::{
-    core::{
-        audio::{AudioBuffer, AudioBufferRef, Signal},
-        codecs::{CODEC_TYPE_NULL, Decoder},
-        conv::{FromSample, IntoSample},
-        errors::Error as SymphoniaError,
-        formats::{FormatReader, SeekMode, SeekTo, SeekedTo, Track},
-        io::MediaSourceStream,
-        probe::{Hint, ProbeResult},
-        sample::{Sample, SampleFormat, i24},
-        units::Time,
-    },
-    default as Symphonia,
+use symphonia::core::{
+    audio::{AudioBuffer, AudioBufferRef, Channels, Signal},
+    conv::IntoSample,
+    formats::{SeekMode, SeekTo, SeekedTo},
+    meta::StandardTagKey,
+    sample::{Sample, SampleFormat, i24},
+    units::{Time, TimeBase},
 };
 use windows::{
     Win32::{
@@ -63,51 +73,366 @@ use windows::{
 };
 use windows_core::implement;
 
+const SUPPORTED_SAMPLE_RATE_RANGE: RangeInclusive<u32> = 4_000..=768_000;
+
+const PLAYBACK_RATE_RANGE: RangeInclusive<f64> = 0.5_f64..=3_f64;
+
 mod standard {
+    use crate::{
+        analysis::AnalysisConfig, channel::StereoImage, decode::DecodeException,
+        dither::DitherMode, dsp::DspStageConfig, resample::ResampleQuality,
+    };
+    use napi::threadsafe_function::{ErrorStrategy, ThreadsafeFunction};
+
+    pub fn to_json_string(value: &str) -> String {
+        let mut json = String::with_capacity(value.len() + 2);
+
+        json.push('"');
+
+        value.chars().for_each(|character| match character {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            character if character < ' ' => {
+                json.push_str(&format!("\\u{:04x}", character as u32));
+            }
+            character => json.push(character),
+        });
+
+        json.push('"');
+
+        json
+    }
+
+    pub fn to_json_number<T: Copy + Into<f64> + ToString>(value: T) -> String {
+        if value.into().is_finite() {
+            value.to_string()
+        } else {
+            String::from("null")
+        }
+    }
+
     pub enum AudioEndpoint {
         Default,
         Custom(String),
     }
 
+    #[derive(Clone)]
+    pub enum VolumeCurve {
+        Logarithmic(f64),
+        Cubic,
+        Linear,
+        Table(Vec<f64>),
+    }
+
+    impl Default for VolumeCurve {
+        fn default() -> Self {
+            Self::Logarithmic(60_f64)
+        }
+    }
+
+    impl VolumeCurve {
+        pub fn map(&self, level: f64) -> f32 {
+            let level = level.clamp(0_f64, 1_f64);
+
+            if level < 0.001 {
+                return 0_f32;
+            }
+
+            match self {
+                Self::Logarithmic(db_dynamic_range) => {
+                    10_f64.powf((level - 1_f64) * db_dynamic_range / 20_f64) as f32
+                }
+                Self::Cubic => level.powi(3) as f32,
+                Self::Linear => level as f32,
+                Self::Table(db_list) => {
+                    let position = level * (db_list.len() - 1) as f64;
+
+                    let index = (position.floor() as usize).min(db_list.len() - 2);
+
+                    let db = db_list[index]
+                        + (db_list[index + 1] - db_list[index]) * (position - index as f64);
+
+                    10_f64.powf(db / 20_f64) as f32
+                }
+            }
+        }
+    }
+
     pub struct Volume {
-        _level_percent_base: f64,
-        amplitude_percent_base: f32,
+        level: Option<f64>,
+        amplitude: f32,
     }
 
     impl Volume {
         pub fn new(value: f64) -> Self {
-            let level_percent_base = value.clamp(0_f64, 1_f64);
-
-            let amplitude_percent_base = if level_percent_base < 0.001 {
-                0_f32
-            } else if level_percent_base > 0.999 {
-                1_f32
+            let level = if value.is_finite() {
+                value.clamp(0_f64, 1_f64)
             } else {
-                let db_dynamic_range = 60_f64;
-
-                10_f64.powf(((level_percent_base * db_dynamic_range) - db_dynamic_range) / 20_f64)
-                    as f32
+                0_f64
             };
 
             Self {
-                _level_percent_base: level_percent_base,
-                amplitude_percent_base,
+                level: Some(level),
+                amplitude: VolumeCurve::default().map(level),
+            }
+        }
+
+        pub fn from_db(db: f64) -> Self {
+            Self {
+                level: None,
+                amplitude: if db.is_nan() || db == f64::NEG_INFINITY {
+                    0_f32
+                } else {
+                    10_f64.powf(db.min(0_f64) / 20_f64) as f32
+                },
+            }
+        }
+
+        pub fn apply_curve(&mut self, volume_curve: &VolumeCurve) {
+            if let Some(level) = self.level {
+                self.amplitude = volume_curve.map(level);
             }
         }
 
         pub fn get(&self) -> f32 {
-            self.amplitude_percent_base
+            self.amplitude
+        }
+
+        pub fn to_json(&self) -> String {
+            let db = 20_f32 * self.amplitude.log10();
+
+            format!(
+                "{{\"amplitude\":{},\"db\":{}}}",
+                to_json_number(self.amplitude),
+                if db.is_finite() {
+                    format!("{:.2}", db)
+                } else {
+                    String::from("null")
+                }
+            )
+        }
+    }
+
+    #[derive(Clone, Copy)]
+    pub struct RampDuration {
+        fade_in: f64,
+        fade_out: f64,
+        volume: f64,
+    }
+
+    impl RampDuration {
+        pub fn new(fade_in: f64, fade_out: f64, volume: f64) -> Self {
+            let clamp = |duration: f64| {
+                if duration.is_finite() {
+                    duration.clamp(0_f64, 1_f64)
+                } else {
+                    0_f64
+                }
+            };
+
+            Self {
+                fade_in: clamp(fade_in),
+                fade_out: clamp(fade_out),
+                volume: clamp(volume),
+            }
+        }
+
+        pub fn get_fade_in(&self) -> f64 {
+            self.fade_in
+        }
+
+        pub fn get_fade_out(&self) -> f64 {
+            self.fade_out
+        }
+
+        pub fn get_volume(&self) -> f64 {
+            self.volume
+        }
+    }
+
+    impl Default for RampDuration {
+        fn default() -> Self {
+            Self::new(0.01_f64, 0.01_f64, 0.02_f64)
+        }
+    }
+
+    #[derive(Clone, Copy, Default)]
+    pub struct LoopPolicy {
+        count: Option<u32>,
+        fade_duration: f64,
+    }
+
+    impl LoopPolicy {
+        pub fn new(count: Option<u32>, fade_duration: f64) -> Self {
+            Self {
+                count,
+                fade_duration: if fade_duration.is_finite() {
+                    fade_duration.clamp(0_f64, 60_f64)
+                } else {
+                    0_f64
+                },
+            }
+        }
+
+        pub fn get_count(&self) -> Option<u32> {
+            self.count
+        }
+
+        pub fn get_fade_duration(&self) -> f64 {
+            self.fade_duration
+        }
+    }
+
+    pub enum CrossfadeCurve {
+        Linear,
+        EqualPower,
+        SCurve,
+    }
+
+    pub struct Crossfade {
+        duration: f64,
+        curve: CrossfadeCurve,
+    }
+
+    impl Crossfade {
+        pub fn new(duration: f64, curve: CrossfadeCurve) -> Self {
+            Self {
+                duration: if duration.is_finite() {
+                    duration.clamp(0_f64, 12_f64)
+                } else {
+                    0_f64
+                },
+                curve,
+            }
+        }
+
+        pub fn get_duration(&self) -> f64 {
+            self.duration
+        }
+
+        pub fn get_gain(&self, progress: f64) -> [f32; 2] {
+            let progress = progress.clamp(0_f64, 1_f64);
+
+            let (fade_out_gain, fade_in_gain) = match self.curve {
+                CrossfadeCurve::Linear => (1_f64 - progress, progress),
+                CrossfadeCurve::EqualPower => (
+                    (progress * std::f64::consts::FRAC_PI_2).cos(),
+                    (progress * std::f64::consts::FRAC_PI_2).sin(),
+                ),
+                CrossfadeCurve::SCurve => {
+                    let fade_in_gain = 0.5_f64 - 0.5_f64 * (progress * std::f64::consts::PI).cos();
+
+                    (1_f64 - fade_in_gain, fade_in_gain)
+                }
+            };
+
+            [fade_out_gain as f32, fade_in_gain as f32]
+        }
+    }
+
+    pub enum ReplayGainMode {
+        Off,
+        Track,
+        Album,
+    }
+
+    #[derive(Default)]
+    pub struct ReplayGainInfo {
+        pub track_gain: Option<f64>,
+        pub track_peak: Option<f64>,
+        pub album_gain: Option<f64>,
+        pub album_peak: Option<f64>,
+    }
+
+    pub struct ReplayGain {
+        mode: ReplayGainMode,
+        preamp: f64,
+        prevent_clipping: bool,
+    }
+
+    impl ReplayGain {
+        pub fn new(mode: ReplayGainMode, preamp: f64, prevent_clipping: bool) -> Self {
+            Self {
+                mode,
+                preamp: if preamp.is_finite() {
+                    preamp.clamp(-15_f64, 15_f64)
+                } else {
+                    0_f64
+                },
+                prevent_clipping,
+            }
+        }
+
+        pub fn get_gain(&self, replay_gain_info: &ReplayGainInfo) -> Option<f64> {
+            match self.mode {
+                ReplayGainMode::Off => None,
+                ReplayGainMode::Track => replay_gain_info
+                    .track_gain
+                    .map(|gain| (gain, replay_gain_info.track_peak))
+                    .or(replay_gain_info
+                        .album_gain
+                        .map(|gain| (gain, replay_gain_info.album_peak))),
+                ReplayGainMode::Album => replay_gain_info
+                    .album_gain
+                    .map(|gain| (gain, replay_gain_info.album_peak))
+                    .or(replay_gain_info
+                        .track_gain
+                        .map(|gain| (gain, replay_gain_info.track_peak))),
+            }
+            .map(|(gain, peak)| {
+                let gain = gain + self.preamp;
+
+                match peak {
+                    Some(peak) if self.prevent_clipping && peak > 0_f64 => {
+                        gain.min(-20_f64 * peak.log10())
+                    }
+                    _ => gain,
+                }
+            })
+        }
+
+        pub fn get_amplitude(&self, replay_gain_info: &ReplayGainInfo) -> f32 {
+            self.get_gain(replay_gain_info)
+                .map_or(1_f32, |gain| 10_f64.powf(gain / 20_f64) as f32)
         }
     }
 
     pub enum Command {
-        SelectMode(AudioEndpoint, Volume),
+        SelectMode(AudioEndpoint, Volume, StereoImage),
         CorrectAudioEndpointDefault,
         CorrectDeviceFormat,
         ModifyVolume(Volume),
+        ModifyVolumeCurve(VolumeCurve),
+        ModifyRampDuration(RampDuration),
+        ModifyCrossfade(Crossfade),
+        ModifyReplayGain(ReplayGain),
+        ModifyChannelMatrix(Option<Vec<Vec<f64>>>),
+        ModifyUpmix(bool),
+        ModifyStereoImage(StereoImage),
+        ModifyDither(DitherMode),
+        ModifyLimiter(Option<f64>),
+        ModifyPlaybackRate(f64, bool),
+        ModifyResampleQuality(ResampleQuality),
+        ModifyLoop(Option<(f64, f64)>),
+        ModifyTagLoop(Option<LoopPolicy>),
+        ModifyDspChain(Vec<(String, bool)>),
+        ModifyDspStage(DspStageConfig),
+        ModifyDspBypass(bool),
+        ModifyAnalysis(
+            Option<(
+                AnalysisConfig,
+                ThreadsafeFunction<String, ErrorStrategy::CalleeHandled>,
+            )>,
+        ),
+        CorrectDuration(u64, f64),
         SelectTrack(String, String),
         ClearTrack,
-        Seek(u32),
+        QueueTrack(String, String),
+        ClearQueuedTrack,
+        Seek(f64),
         Play,
         Pause,
         Stop,
@@ -126,6 +451,15 @@ mod standard {
         InvalidFile,
         IncorrectFile,
     }
+
+    impl From<DecodeException> for SourceException {
+        fn from(error: DecodeException) -> Self {
+            match error {
+                DecodeException::InvalidFile => SourceException::InvalidFile,
+                DecodeException::IncorrectFile => SourceException::IncorrectFile,
+            }
+        }
+    }
 }
 
 use standard::*;
@@ -240,11 +574,29 @@ mod scheduler {
     }
 
     #[derive(PartialEq, Eq, Hash)]
-    enum FilterTag {
+    enum FilterTag<'a> {
         ModeIsSelected,
         AudioEndpointDefaultIsCorrected,
         VolumeIsModified,
+        VolumeCurveIsModified,
+        RampDurationIsModified,
+        CrossfadeIsModified,
+        ReplayGainIsModified,
+        ChannelMatrixIsModified,
+        UpmixIsModified,
+        StereoImageIsModified,
+        DitherIsModified,
+        LimiterIsModified,
+        PlaybackRateIsModified,
+        ResampleQualityIsModified,
+        LoopIsModified,
+        TagLoopIsModified,
+        DspChainIsModified,
+        DspStageIsModified(&'a str),
+        DspBypassIsModified,
+        AnalysisIsModified,
         TrackIsUpdated,
+        QueuedTrackIsUpdated,
         ProgressIsUpdated,
         StateIsChanged,
     }
@@ -259,6 +611,32 @@ mod scheduler {
         }
 
         pub fn backlog_into_iter(&self, wait: bool) -> impl Iterator<Item = Command> {
+            let mut backlog_command_vector = Vec::new();
+
+            if wait {
+                if let Ok(command) = self.rx.recv() {
+                    backlog_command_vector.push(command);
+                } else {
+                    backlog_command_vector.push(Command::Close);
+                }
+            }
+
+            while !matches!(backlog_command_vector.last(), Some(Command::Close)) {
+                match self.rx.try_recv() {
+                    Ok(command) => {
+                        backlog_command_vector.push(command);
+                    }
+                    Err(mpsc::TryRecvError::Empty) => break,
+                    Err(mpsc::TryRecvError::Disconnected) => {
+                        backlog_command_vector.push(Command::Close);
+                    }
+                }
+            }
+
+            Self::filter(backlog_command_vector).into_iter()
+        }
+
+        pub fn filter(command_vector: Vec<Command>) -> VecDeque<Command> {
             let mut coarse_filter_tag_set = HashSet::new();
 
             let mut coarse_fill_vector = |command: Command,
@@ -269,7 +647,7 @@ mod scheduler {
                 let finish = matches!(command, Command::Close);
 
                 match command {
-                    command @ Command::SelectMode(_, _) => {
+                    command @ Command::SelectMode(_, _, _) => {
                         coarse_filtered_backlog_command_vector.push_front(command);
 
                         coarse_filter_tag_set.insert(CoarseFilterTag::ModeIsSelected);
@@ -307,34 +685,9 @@ mod scheduler {
 
             let mut coarse_filtered_backlog_command_vector = VecDeque::new();
 
-            if wait {
-                if let Ok(command) = self.rx.recv() {
-                    coarse_fill_vector(command, &mut coarse_filtered_backlog_command_vector);
-                } else {
-                    coarse_filtered_backlog_command_vector.push_front(Command::Close);
-                }
-            }
-
-            if let Some(command) = coarse_filtered_backlog_command_vector.back()
-                && matches!(command, Command::Close)
-            {
-                return coarse_filtered_backlog_command_vector.into_iter();
-            }
-
-            loop {
-                match self.rx.try_recv() {
-                    Ok(command) => {
-                        if coarse_fill_vector(command, &mut coarse_filtered_backlog_command_vector)
-                        {
-                            break;
-                        }
-                    }
-                    Err(mpsc::TryRecvError::Empty) => break,
-                    Err(mpsc::TryRecvError::Disconnected) => {
-                        coarse_filtered_backlog_command_vector.push_front(Command::Close);
-
-                        break;
-                    }
+            for command in command_vector {
+                if coarse_fill_vector(command, &mut coarse_filtered_backlog_command_vector) {
+                    break;
                 }
             }
 
@@ -346,7 +699,7 @@ mod scheduler {
                 let finish = matches!(command, Command::Close);
 
                 match command {
-                    command @ Command::SelectMode(_, _) => {
+                    command @ Command::SelectMode(_, _, _) => {
                         if !filter_tag_set.contains(&FilterTag::ModeIsSelected) {
                             filtered_backlog_command_vector.push_front(command);
 
@@ -376,6 +729,130 @@ mod scheduler {
                             filter_tag_set.insert(FilterTag::VolumeIsModified);
                         }
                     }
+                    command @ Command::ModifyVolumeCurve(_) => {
+                        if !filter_tag_set.contains(&FilterTag::VolumeCurveIsModified) {
+                            filtered_backlog_command_vector.push_front(command);
+
+                            filter_tag_set.insert(FilterTag::VolumeCurveIsModified);
+                        }
+                    }
+                    command @ Command::ModifyRampDuration(_) => {
+                        if !filter_tag_set.contains(&FilterTag::RampDurationIsModified) {
+                            filtered_backlog_command_vector.push_front(command);
+
+                            filter_tag_set.insert(FilterTag::RampDurationIsModified);
+                        }
+                    }
+                    command @ Command::ModifyCrossfade(_) => {
+                        if !filter_tag_set.contains(&FilterTag::CrossfadeIsModified) {
+                            filtered_backlog_command_vector.push_front(command);
+
+                            filter_tag_set.insert(FilterTag::CrossfadeIsModified);
+                        }
+                    }
+                    command @ Command::ModifyReplayGain(_) => {
+                        if !filter_tag_set.contains(&FilterTag::ReplayGainIsModified) {
+                            filtered_backlog_command_vector.push_front(command);
+
+                            filter_tag_set.insert(FilterTag::ReplayGainIsModified);
+                        }
+                    }
+                    command @ Command::ModifyChannelMatrix(_) => {
+                        if !filter_tag_set.contains(&FilterTag::ChannelMatrixIsModified) {
+                            filtered_backlog_command_vector.push_front(command);
+
+                            filter_tag_set.insert(FilterTag::ChannelMatrixIsModified);
+                        }
+                    }
+                    command @ Command::ModifyStereoImage(_) => {
+                        if !(filter_tag_set.contains(&FilterTag::ModeIsSelected)
+                            || filter_tag_set.contains(&FilterTag::StereoImageIsModified))
+                        {
+                            filtered_backlog_command_vector.push_front(command);
+
+                            filter_tag_set.insert(FilterTag::StereoImageIsModified);
+                        }
+                    }
+                    command @ Command::ModifyDither(_) => {
+                        if !filter_tag_set.contains(&FilterTag::DitherIsModified) {
+                            filtered_backlog_command_vector.push_front(command);
+
+                            filter_tag_set.insert(FilterTag::DitherIsModified);
+                        }
+                    }
+                    command @ Command::ModifyLimiter(_) => {
+                        if !filter_tag_set.contains(&FilterTag::LimiterIsModified) {
+                            filtered_backlog_command_vector.push_front(command);
+
+                            filter_tag_set.insert(FilterTag::LimiterIsModified);
+                        }
+                    }
+                    command @ Command::ModifyUpmix(_) => {
+                        if !filter_tag_set.contains(&FilterTag::UpmixIsModified) {
+                            filtered_backlog_command_vector.push_front(command);
+
+                            filter_tag_set.insert(FilterTag::UpmixIsModified);
+                        }
+                    }
+                    command @ Command::ModifyPlaybackRate(_, _) => {
+                        if !filter_tag_set.contains(&FilterTag::PlaybackRateIsModified) {
+                            filtered_backlog_command_vector.push_front(command);
+
+                            filter_tag_set.insert(FilterTag::PlaybackRateIsModified);
+                        }
+                    }
+                    command @ Command::ModifyResampleQuality(_) => {
+                        if !filter_tag_set.contains(&FilterTag::ResampleQualityIsModified) {
+                            filtered_backlog_command_vector.push_front(command);
+
+                            filter_tag_set.insert(FilterTag::ResampleQualityIsModified);
+                        }
+                    }
+                    command @ Command::ModifyLoop(_) => {
+                        if !filter_tag_set.contains(&FilterTag::LoopIsModified) {
+                            filtered_backlog_command_vector.push_front(command);
+
+                            filter_tag_set.insert(FilterTag::LoopIsModified);
+                        }
+                    }
+                    command @ Command::ModifyTagLoop(_) => {
+                        if !filter_tag_set.contains(&FilterTag::TagLoopIsModified) {
+                            filtered_backlog_command_vector.push_front(command);
+
+                            filter_tag_set.insert(FilterTag::TagLoopIsModified);
+                        }
+                    }
+                    command @ Command::ModifyDspChain(_) => {
+                        if !filter_tag_set.contains(&FilterTag::DspChainIsModified) {
+                            filtered_backlog_command_vector.push_front(command);
+
+                            filter_tag_set.insert(FilterTag::DspChainIsModified);
+                        }
+                    }
+                    Command::ModifyDspStage(config) => {
+                        let id = config.get_id();
+
+                        if !filter_tag_set.contains(&FilterTag::DspStageIsModified(id)) {
+                            filtered_backlog_command_vector
+                                .push_front(Command::ModifyDspStage(config));
+
+                            filter_tag_set.insert(FilterTag::DspStageIsModified(id));
+                        }
+                    }
+                    command @ Command::ModifyDspBypass(_) => {
+                        if !filter_tag_set.contains(&FilterTag::DspBypassIsModified) {
+                            filtered_backlog_command_vector.push_front(command);
+
+                            filter_tag_set.insert(FilterTag::DspBypassIsModified);
+                        }
+                    }
+                    command @ Command::ModifyAnalysis(_) => {
+                        if !filter_tag_set.contains(&FilterTag::AnalysisIsModified) {
+                            filtered_backlog_command_vector.push_front(command);
+
+                            filter_tag_set.insert(FilterTag::AnalysisIsModified);
+                        }
+                    }
                     command @ (Command::SelectTrack(_, _) | Command::ClearTrack) => {
                         if !filter_tag_set.contains(&FilterTag::TrackIsUpdated) {
                             filtered_backlog_command_vector.push_front(command);
@@ -383,6 +860,13 @@ mod scheduler {
                             filter_tag_set.insert(FilterTag::TrackIsUpdated);
                         }
                     }
+                    command @ (Command::QueueTrack(_, _) | Command::ClearQueuedTrack) => {
+                        if !filter_tag_set.contains(&FilterTag::QueuedTrackIsUpdated) {
+                            filtered_backlog_command_vector.push_front(command);
+
+                            filter_tag_set.insert(FilterTag::QueuedTrackIsUpdated);
+                        }
+                    }
                     command @ Command::Seek(_) => {
                         if !filter_tag_set.contains(&FilterTag::ProgressIsUpdated) {
                             filtered_backlog_command_vector.push_front(command);
@@ -408,7 +892,7 @@ mod scheduler {
                             filter_tag_set.insert(FilterTag::StateIsChanged);
                         } else {
                             if !filter_tag_set.contains(&FilterTag::ProgressIsUpdated) {
-                                filtered_backlog_command_vector.push_front(Command::Seek(0));
+                                filtered_backlog_command_vector.push_front(Command::Seek(0_f64));
 
                                 filter_tag_set.insert(FilterTag::ProgressIsUpdated);
                             }
@@ -420,7 +904,7 @@ mod scheduler {
                             }
                         }
                     }
-                    command @ Command::Close => {
+                    command @ (Command::CorrectDuration(_, _) | Command::Close) => {
                         filtered_backlog_command_vector.push_front(command);
                     }
                 }
@@ -436,7 +920,7 @@ mod scheduler {
                 }
             }
 
-            filtered_backlog_command_vector.into_iter()
+            filtered_backlog_command_vector
         }
     }
 
@@ -483,6 +967,7 @@ mod scheduler {
             audio_client: IAudioClient,
             mix_format_ptr: *const WAVEFORMATEX,
             expected_format: (SampleFormat, u32),
+            speaker_layout: Channels,
             buffer_frame_count: u32,
             audio_clock: IAudioClock,
             audio_render_client: IAudioRenderClient,
@@ -507,8 +992,10 @@ mod scheduler {
                             .and_then(|mix_format_ptr| {
                                 let sample_rate = unsafe { *mix_format_ptr }.nSamplesPerSec;
 
-                                (unsafe { *mix_format_ptr }.nChannels == 2 &&
-                                    (44_100..=192_000).contains(&sample_rate))
+                                let channel_count = unsafe { *mix_format_ptr }.nChannels as usize;
+
+                                ((1..=MAX_CHANNEL_COUNT).contains(&channel_count) &&
+                                    SUPPORTED_SAMPLE_RATE_RANGE.contains(&sample_rate))
                                     .then_some(sample_rate)
                                     .and_then(|sample_rate| {
                                         let mix_format_ref = &unsafe { *mix_format_ptr };
@@ -547,6 +1034,18 @@ mod scheduler {
                                     })
                                     .ok_or(DeviceException::UnsupportedDeviceFormat)
                                     .and_then(|expected_format| {
+                                        let speaker_layout = resolve_speaker_layout(
+                                            channel_count,
+                                            if unsafe { *mix_format_ptr }.wFormatTag as u32 == WAVE_FORMAT_EXTENSIBLE {
+                                                unsafe {
+                                                    *(mix_format_ptr as *const _ as *const WAVEFORMATEXTENSIBLE)
+                                                }
+                                                .dwChannelMask
+                                            } else {
+                                                0
+                                            },
+                                        );
+
                                         unsafe {
                                             audio_client.Initialize(
                                                 AUDCLNT_SHAREMODE_SHARED,
@@ -580,6 +1079,7 @@ mod scheduler {
                                                                                 audio_client,
                                                                                 mix_format_ptr,
                                                                                 expected_format,
+                                                                                speaker_layout,
                                                                                 buffer_frame_count,
                                                                                 audio_clock,
                                                                                 audio_render_client,
@@ -601,9 +1101,20 @@ mod scheduler {
 
         pub struct DeviceScheduler<'b, 'c> {
             device: IMMDevice,
+            endpoint_id: String,
             correctable_parts: CorrectableParts,
             event_handle: &'b HANDLE,
             audio_session_events: &'c IAudioSessionEvents,
+            channel_matrix: ChannelMatrix,
+            upmix_is_enabled: bool,
+            stereo_image: StereoImage,
+            dither_mode: DitherMode,
+            ditherer: Option<Ditherer>,
+            limiter_ceiling: Option<f64>,
+            limiter: Option<TruePeakLimiter>,
+            analysis_config: Option<AnalysisConfig>,
+            analysis_tap: Option<AnalysisTap>,
+            written_frame_count: u64,
             has_started: bool,
             has_finished: bool,
         }
@@ -658,12 +1169,31 @@ mod scheduler {
                     }),
                 }
                 .and_then(|device| {
+                    let endpoint_id = unsafe { device.GetId() }
+                        .ok()
+                        .and_then(|id| unsafe { id.to_string() }.ok())
+                        .unwrap_or_default();
+
                     CorrectableParts::try_from((&device, event_handle, audio_session_events)).map(
                         |correctable_parts| Self {
                             device,
+                            endpoint_id,
+                            channel_matrix: ChannelMatrix::upmix_from_stereo(
+                                correctable_parts.speaker_layout,
+                                false,
+                            ),
                             correctable_parts,
                             event_handle,
                             audio_session_events,
+                            upmix_is_enabled: false,
+                            stereo_image: StereoImage::default(),
+                            dither_mode: DitherMode::None,
+                            ditherer: None,
+                            limiter_ceiling: None,
+                            limiter: None,
+                            analysis_config: None,
+                            analysis_tap: None,
+                            written_frame_count: 0,
                             has_started: false,
                             has_finished: false,
                         },
@@ -673,22 +1203,6 @@ mod scheduler {
         }
 
         impl<'b, 'c> DeviceScheduler<'b, 'c> {
-            fn fill_to_buffer_slice<T>(
-                buffer_slice: &mut [T],
-                mut source: IntoIter<[f32; 2]>,
-                volume: f32,
-            ) where
-                T: FromSample<f32>,
-            {
-                for step_index in (0..buffer_slice.len()).step_by(2) {
-                    let [left, right] = source.next().unwrap_or([0_f32, 0_f32]);
-
-                    buffer_slice[step_index] = (left * volume).into_sample();
-
-                    buffer_slice[step_index + 1] = (right * volume).into_sample();
-                }
-            }
-
             fn dispose(&mut self) {
                 self.pause();
 
@@ -706,11 +1220,86 @@ mod scheduler {
                 self.expected_format.1
             }
 
-            pub fn get_has_started(&self) -> bool {
-                self.has_started
+            pub fn get_endpoint_id(&self) -> &str {
+                &self.endpoint_id
             }
 
-            pub fn get_expected_frame_count(&mut self) -> Result<u32, DeviceException> {
+            pub fn set_upmix(&mut self, upmix_is_enabled: bool) {
+                self.upmix_is_enabled = upmix_is_enabled;
+
+                self.channel_matrix = ChannelMatrix::upmix_from_stereo(
+                    self.correctable_parts.speaker_layout,
+                    self.upmix_is_enabled,
+                );
+            }
+
+            pub fn set_stereo_image(&mut self, stereo_image: StereoImage) {
+                self.stereo_image = stereo_image;
+            }
+
+            pub fn set_dither(&mut self, dither_mode: DitherMode) {
+                self.dither_mode = dither_mode;
+
+                self.ditherer = match self.expected_format.0 {
+                    SampleFormat::S8 => Some(8),
+                    SampleFormat::S16 => Some(16),
+                    SampleFormat::S24 => Some(24),
+                    _ => None,
+                }
+                .and_then(|bit_depth| {
+                    Ditherer::new(
+                        self.dither_mode,
+                        bit_depth,
+                        self.channel_matrix.get_output_count(),
+                    )
+                });
+            }
+
+            pub fn set_limiter(&mut self, limiter_ceiling: Option<f64>) {
+                self.limiter_ceiling = limiter_ceiling;
+
+                self.limiter = self.limiter_ceiling.map(|limiter_ceiling| {
+                    TruePeakLimiter::new(limiter_ceiling, self.get_sample_rate())
+                });
+            }
+
+            pub fn get_latency(&self) -> u32 {
+                self.limiter
+                    .as_ref()
+                    .map_or(0, |limiter| limiter.get_latency())
+            }
+
+            pub fn take_gain_reduction(&mut self) -> f64 {
+                self.limiter
+                    .as_mut()
+                    .map_or(0_f64, |limiter| limiter.take_gain_reduction())
+            }
+
+            pub fn set_analysis(&mut self, analysis_config: Option<AnalysisConfig>) {
+                self.analysis_config = analysis_config;
+
+                self.analysis_tap = self.analysis_config.as_ref().map(|analysis_config| {
+                    AnalysisTap::new(analysis_config, self.get_sample_rate())
+                });
+            }
+
+            pub fn request_analysis(&mut self, timeline_offset: f64) -> Option<AnalysisFrame> {
+                let sample_rate = self.get_sample_rate();
+
+                self.analysis_tap.as_mut().and_then(|analysis_tap| {
+                    analysis_tap.request((timeline_offset * sample_rate as f64).round() as u64)
+                })
+            }
+
+            pub fn get_has_started(&self) -> bool {
+                self.has_started
+            }
+
+            pub fn get_written_frame_count(&self) -> u64 {
+                self.written_frame_count
+            }
+
+            pub fn get_expected_frame_count(&mut self) -> Result<u32, DeviceException> {
                 if self.has_started
                     && unsafe { WaitForSingleObject(*self.event_handle, 2000) } != WAIT_OBJECT_0
                 {
@@ -739,7 +1328,7 @@ mod scheduler {
             pub fn play(
                 &mut self,
                 source: Option<SourceStream>,
-                volume: f32,
+                gain_ramp: &mut GainRamp,
             ) -> Result<bool, DeviceException> {
                 if !self.has_finished {
                     let mut drain_is_necessary = false;
@@ -759,154 +1348,174 @@ mod scheduler {
                             None
                         }
                     } {
-                        let source_frame_count = source.len();
+                        let mut source = source
+                            .into_iter()
+                            .map(|frame| {
+                                let gain = gain_ramp.request_gain();
 
-                        match unsafe {
-                            self.audio_render_client
-                                .GetBuffer(source_frame_count as u32)
-                        }
-                        .map(|buffer_ptr| {
-                            if source_frame_count == 0 {
-                                return;
-                            }
+                                self.stereo_image.apply(frame.map(|sample| sample * gain))
+                            })
+                            .collect::<Vec<_>>();
 
-                            let mut source = source.into_iter();
+                        if let Some(limiter) = self.limiter.as_mut() {
+                            limiter.process(&mut source);
+                        }
 
-                            match self.expected_format.0 {
-                                SampleFormat::S8 => {
-                                    let buffer_slice = unsafe {
-                                        slice::from_raw_parts_mut(
-                                            buffer_ptr as *mut i8,
-                                            source_frame_count * 2,
-                                        )
-                                    };
+                        self.write(source)?;
+                    };
 
-                                    Self::fill_to_buffer_slice(buffer_slice, source, volume);
-                                }
-                                SampleFormat::S16 => {
-                                    let buffer_slice = unsafe {
-                                        slice::from_raw_parts_mut(
-                                            buffer_ptr as *mut i16,
-                                            source_frame_count * 2,
-                                        )
-                                    };
+                    if drain_is_necessary {
+                        if let Some(limiter) = self.limiter.as_mut() {
+                            let mut tail = vec![[0_f32; 2]; limiter.get_latency() as usize];
 
-                                    Self::fill_to_buffer_slice(buffer_slice, source, volume);
-                                }
-                                SampleFormat::S24 => {
-                                    let buffer_slice = unsafe {
-                                        slice::from_raw_parts_mut(
-                                            buffer_ptr,
-                                            source_frame_count * 6,
-                                        )
-                                    };
+                            limiter.process(&mut tail);
 
-                                    for step_index in (0..buffer_slice.len()).step_by(6) {
-                                        let [left, right] = source.next().unwrap_or([0_f32, 0_f32]);
+                            self.wait_for_space(tail.len() as u32)?;
 
-                                        let left: i24 = (left * volume).into_sample();
+                            self.write(tail)?;
+                        }
 
-                                        let [left_0, left_1, left_2] = left.to_ne_bytes();
+                        self.drain()?;
 
-                                        let right: i24 = (right * volume).into_sample();
+                        self.has_finished = true;
 
-                                        let [right_0, right_1, right_2] = right.to_ne_bytes();
+                        Ok(true)
+                    } else {
+                        Ok(false)
+                    }
+                } else {
+                    Ok(false)
+                }
+            }
 
-                                        buffer_slice[step_index] = left_0;
+            fn write(&mut self, source: Vec<[f32; 2]>) -> Result<(), DeviceException> {
+                let source_frame_count = source.len();
 
-                                        buffer_slice[step_index + 1] = left_1;
+                if let Some(analysis_tap) = self.analysis_tap.as_mut() {
+                    source.iter().for_each(|frame| {
+                        analysis_tap.push(*frame);
+                    });
+                }
 
-                                        buffer_slice[step_index + 2] = left_2;
+                match unsafe {
+                    self.audio_render_client
+                        .GetBuffer(source_frame_count as u32)
+                }
+                .map(|buffer_ptr| {
+                    if source_frame_count == 0 {
+                        return;
+                    }
 
-                                        buffer_slice[step_index + 3] = right_0;
+                    let sample_count = source_frame_count * self.channel_matrix.get_output_count();
 
-                                        buffer_slice[step_index + 4] = right_1;
+                    let buffer_slice = |byte_length: usize| unsafe {
+                        slice::from_raw_parts_mut(buffer_ptr, sample_count * byte_length)
+                    };
 
-                                        buffer_slice[step_index + 5] = right_2;
-                                    }
+                    match self.expected_format.0 {
+                        SampleFormat::S8 => interleave::<i8>(
+                            buffer_slice(i8::BYTE_LENGTH),
+                            source,
+                            &self.channel_matrix,
+                            self.ditherer.as_mut(),
+                        ),
+                        SampleFormat::S16 => interleave::<i16>(
+                            buffer_slice(i16::BYTE_LENGTH),
+                            source,
+                            &self.channel_matrix,
+                            self.ditherer.as_mut(),
+                        ),
+                        SampleFormat::S24 => interleave::<i24>(
+                            buffer_slice(i24::BYTE_LENGTH),
+                            source,
+                            &self.channel_matrix,
+                            self.ditherer.as_mut(),
+                        ),
+                        SampleFormat::S32 => interleave::<i32>(
+                            buffer_slice(i32::BYTE_LENGTH),
+                            source,
+                            &self.channel_matrix,
+                            None,
+                        ),
+                        SampleFormat::F32 => interleave::<f32>(
+                            buffer_slice(f32::BYTE_LENGTH),
+                            source,
+                            &self.channel_matrix,
+                            None,
+                        ),
+                        _ => unreachable!(),
+                    }
+                })
+                .and_then(|_| unsafe {
+                    self.audio_render_client
+                        .ReleaseBuffer(source_frame_count as u32, 0)
+                }) {
+                    Ok(_) => {
+                        self.written_frame_count += source_frame_count as u64;
+
+                        if !self.has_started {
+                            match unsafe { self.audio_client.Start() } {
+                                Ok(_) => {
+                                    self.has_started = true;
                                 }
-                                SampleFormat::S32 => {
-                                    let buffer_slice = unsafe {
-                                        slice::from_raw_parts_mut(
-                                            buffer_ptr as *mut i32,
-                                            source_frame_count * 2,
-                                        )
-                                    };
-
-                                    Self::fill_to_buffer_slice(buffer_slice, source, volume);
+                                Err(_) => {
+                                    return Err(DeviceException::SilentException);
                                 }
-                                SampleFormat::F32 => {
-                                    let buffer_slice = unsafe {
-                                        slice::from_raw_parts_mut(
-                                            buffer_ptr as *mut f32,
-                                            source_frame_count * 2,
-                                        )
-                                    };
+                            }
+                        }
+                    }
+                    Err(_) => {
+                        self.pause();
 
-                                    for step_index in (0..buffer_slice.len()).step_by(2) {
-                                        let [left, right] = source.next().unwrap_or([0_f32, 0_f32]);
+                        return Err(DeviceException::SilentException);
+                    }
+                }
 
-                                        buffer_slice[step_index] = left * volume;
+                Ok(())
+            }
 
-                                        buffer_slice[step_index + 1] = right * volume;
-                                    }
-                                }
-                                _ => unreachable!(),
+            fn wait_for_space(&mut self, frame_count: u32) -> Result<(), DeviceException> {
+                while self.has_started {
+                    match unsafe { self.audio_client.GetCurrentPadding() } {
+                        Ok(unread_frame_count) => {
+                            if self.buffer_frame_count - unread_frame_count >= frame_count {
+                                break;
                             }
-                        })
-                        .and_then(|_| unsafe {
-                            self.audio_render_client
-                                .ReleaseBuffer(source_frame_count as u32, 0)
-                        }) {
-                            Ok(_) => {
-                                if !self.has_started {
-                                    match unsafe { self.audio_client.Start() } {
-                                        Ok(_) => {
-                                            self.has_started = true;
-                                        }
-                                        Err(_) => {
-                                            return Err(DeviceException::SilentException);
-                                        }
-                                    }
-                                }
-                            }
-                            Err(_) => {
-                                self.pause();
+                        }
+                        Err(_) => {
+                            self.pause();
 
-                                return Err(DeviceException::SilentException);
-                            }
-                        };
-                    };
+                            return Err(DeviceException::SilentException);
+                        }
+                    }
 
-                    if drain_is_necessary {
-                        loop {
-                            match unsafe { self.audio_client.GetCurrentPadding() } {
-                                Ok(unread_frame_count) => {
-                                    if unread_frame_count == 0 {
-                                        break;
-                                    }
-                                }
-                                Err(_) => {
-                                    self.pause();
+                    thread::sleep(Duration::from_millis(1));
+                }
 
-                                    return Err(DeviceException::SilentException);
-                                }
+                Ok(())
+            }
+
+            pub fn drain(&mut self) -> Result<(), DeviceException> {
+                loop {
+                    match unsafe { self.audio_client.GetCurrentPadding() } {
+                        Ok(unread_frame_count) => {
+                            if unread_frame_count == 0 {
+                                break;
                             }
+                        }
+                        Err(_) => {
+                            self.pause();
 
-                            thread::sleep(Duration::from_millis(10));
+                            return Err(DeviceException::SilentException);
                         }
+                    }
 
-                        self.pause();
+                    thread::sleep(Duration::from_millis(10));
+                }
 
-                        self.has_finished = true;
+                self.pause();
 
-                        Ok(true)
-                    } else {
-                        Ok(false)
-                    }
-                } else {
-                    Ok(false)
-                }
+                Ok(())
             }
 
             pub fn pause(&mut self) {
@@ -929,6 +1538,16 @@ mod scheduler {
                 ))
                 .map(|correctable_parts| {
                     self.correctable_parts = correctable_parts;
+
+                    self.set_upmix(self.upmix_is_enabled);
+
+                    self.set_dither(self.dither_mode);
+
+                    self.set_limiter(self.limiter_ceiling);
+
+                    self.set_analysis(self.analysis_config);
+
+                    self.written_frame_count = 0;
                 })
             }
 
@@ -937,6 +1556,16 @@ mod scheduler {
 
                 let _ = unsafe { self.audio_client.Reset() };
 
+                if let Some(limiter) = self.limiter.as_mut() {
+                    limiter.reset();
+                }
+
+                if let Some(analysis_tap) = self.analysis_tap.as_mut() {
+                    analysis_tap.reset();
+                }
+
+                self.written_frame_count = 0;
+
                 self.has_finished = false;
             }
         }
@@ -955,248 +1584,780 @@ mod scheduler {
         Break(Vec<[f32; 2]>),
     }
 
-    pub struct SourceScheduler<'a> {
-        format: Box<dyn FormatReader>,
-        track_id: u32,
+    impl SourceStream {
+        pub fn len(source: Option<&Self>) -> usize {
+            match source {
+                Some(SourceStream::Continue(source) | SourceStream::Break(source)) => source.len(),
+                None => 0,
+            }
+        }
+
+        pub fn splice(source: Option<Self>, next_source: Option<Self>) -> Option<Self> {
+            let mut source = match source {
+                Some(SourceStream::Continue(source) | SourceStream::Break(source)) => source,
+                None => Vec::new(),
+            };
+
+            match next_source {
+                Some(SourceStream::Continue(next_source)) => {
+                    source.extend(next_source);
+
+                    Some(SourceStream::Continue(source))
+                }
+                Some(SourceStream::Break(next_source)) => {
+                    source.extend(next_source);
+
+                    Some(SourceStream::Break(source))
+                }
+                None => (!source.is_empty()).then_some(SourceStream::Break(source)),
+            }
+        }
+
+        pub fn amplify(source: Option<Self>, amplitude: f32) -> Option<Self> {
+            source.map(|mut source| {
+                if amplitude != 1_f32 {
+                    let (SourceStream::Continue(frames) | SourceStream::Break(frames)) =
+                        &mut source;
+
+                    for [left, right] in frames.iter_mut() {
+                        *left *= amplitude;
+
+                        *right *= amplitude;
+                    }
+                }
+
+                source
+            })
+        }
+
+        pub fn process(source: Option<Self>, dsp_chain: &mut DspChain) -> Option<Self> {
+            source.map(|mut source| {
+                let (SourceStream::Continue(frames) | SourceStream::Break(frames)) = &mut source;
+
+                dsp_chain.process(frames);
+
+                source
+            })
+        }
+
+        pub fn mix<F>(fading_source: Option<Self>, source: Option<Self>, gain: F) -> Option<Self>
+        where
+            F: Fn(usize) -> [f32; 2],
+        {
+            let is_continue = matches!(fading_source, Some(SourceStream::Continue(_)))
+                || matches!(source, Some(SourceStream::Continue(_)));
+
+            let fading_source = match fading_source {
+                Some(SourceStream::Continue(source) | SourceStream::Break(source)) => source,
+                None => Vec::new(),
+            };
+
+            let source = match source {
+                Some(SourceStream::Continue(source) | SourceStream::Break(source)) => source,
+                None => Vec::new(),
+            };
+
+            let mixed_source = (0..fading_source.len().max(source.len()))
+                .map(|index| {
+                    let [fading_left, fading_right] =
+                        fading_source.get(index).copied().unwrap_or([0_f32, 0_f32]);
+
+                    let [left, right] = source.get(index).copied().unwrap_or([0_f32, 0_f32]);
+
+                    let [fade_out_gain, fade_in_gain] = gain(index);
+
+                    [
+                        fading_left * fade_out_gain + left * fade_in_gain,
+                        fading_right * fade_out_gain + right * fade_in_gain,
+                    ]
+                })
+                .collect::<Vec<[f32; 2]>>();
+
+            if is_continue {
+                Some(SourceStream::Continue(mixed_source))
+            } else {
+                (!mixed_source.is_empty()).then_some(SourceStream::Break(mixed_source))
+            }
+        }
+    }
+
+    static SOURCE_SCHEDULER_SERIAL: AtomicU64 = AtomicU64::new(0);
+
+    pub struct SourceScheduler {
+        serial: u64,
+        duration_refinement_is_cancelled: Arc<AtomicBool>,
+        path: String,
+        media_source: MediaSource,
+        time_base: TimeBase,
         sample_rate: u32,
         output_sample_rate: u32,
-        duration: u32,
-        decoder: Box<dyn Decoder>,
-        resampler: &'a mut SincFixedIn<f32>,
+        timeline_length: Option<f64>,
+        timeline_position: f64,
+        album: Option<String>,
+        replay_gain_info: ReplayGainInfo,
+        resample_quality: ResampleQuality,
+        resampler: SourceResampler,
         resampler_delay_count: u32,
         has_trimmed_delay: bool,
         resampler_input_count: u32,
         resampler_output_count: u32,
-        packet_buffer: [VecDeque<f32>; 2],
+        seek_trim_frame_count: usize,
+        frame_position: u64,
+        tag_loop_frame_range: Option<(u64, u64)>,
+        tag_loop_policy: Option<LoopPolicy>,
+        custom_loop_frame_range: Option<(u64, u64)>,
+        loop_frame_range: Option<(u64, u64)>,
+        loop_policy: LoopPolicy,
+        loop_remaining_count: Option<u32>,
+        fade_out_frame_count: Option<(u64, u64)>,
+        has_ended: bool,
+        channel_count: usize,
+        channel_matrix: ChannelMatrix,
+        playback_rate: f64,
+        time_stretcher: Option<TimeStretcher>,
+        packet_buffer: Vec<VecDeque<f32>>,
         source_buffer: VecDeque<[f32; 2]>,
+        stretched_buffer: VecDeque<[f32; 2]>,
+    }
+
+    impl Drop for SourceScheduler {
+        fn drop(&mut self) {
+            self.duration_refinement_is_cancelled
+                .store(true, Ordering::Relaxed);
+        }
     }
 
-    impl<'a> TryFrom<(&String, &'a mut SincFixedIn<f32>)> for SourceScheduler<'a> {
+    impl TryFrom<&String> for SourceScheduler {
         type Error = SourceException;
 
-        fn try_from(
-            (path, resampler): (&String, &'a mut SincFixedIn<f32>),
-        ) -> Result<Self, Self::Error> {
-            File::open(path)
-                .map_err(|_| SourceException::InvalidFile)
-                .and_then(|file| {
-                    let mut hint = Hint::new();
-
-                    if path.ends_with(".flac") {
-                        hint.with_extension("flac");
-                    } else if path.ends_with(".mp3") {
-                        hint.with_extension("mp3");
-                    }
-
-                    Symphonia::get_probe()
-                        .format(
-                            &hint,
-                            MediaSourceStream::new(Box::new(file), Default::default()),
-                            &Default::default(),
-                            &Default::default(),
-                        )
-                        .map_err(|_| SourceException::InvalidFile)
-                })
-                .and_then(|ProbeResult { format, .. }| {
-                    format
-                        .tracks()
-                        .iter()
-                        .find(|track| track.codec_params.codec != CODEC_TYPE_NULL)
-                        .and_then(
-                            |Track {
-                                 id, codec_params, ..
-                             }| {
-                                codec_params
-                                    .channels
-                                    .and_then(|channels| {
-                                        (channels.count() == 2)
-                                            .then_some(())
-                                            .and(codec_params.sample_rate)
-                                    })
-                                    .and_then(|sample_rate| {
-                                        (44_100..=192_000)
-                                            .contains(&sample_rate)
-                                            .then_some(sample_rate)
-                                    })
-                                    .and_then(|sample_rate| {
-                                        codec_params
-                                            .time_base
-                                            .and_then(|time_base| {
-                                                codec_params.n_frames.map(|frame_count| {
-                                                    time_base.calc_time(frame_count).seconds as u32
-                                                })
-                                            })
-                                            .and_then(|duration| {
-                                                Symphonia::get_codecs()
-                                                    .make(codec_params, &Default::default())
-                                                    .map(|decoder| {
-                                                        (*id, sample_rate, duration, decoder)
-                                                    })
-                                                    .ok()
-                                            })
-                                    })
-                            },
-                        )
-                        .inspect(|_| {
-                            resampler.reset();
+        fn try_from(path: &String) -> Result<Self, Self::Error> {
+            MediaSource::try_from(path.as_str())
+                .map_err(SourceException::from)
+                .and_then(|media_source| {
+                    let codec_params = &media_source.codec_params;
+
+                    codec_params
+                        .channels
+                        .filter(|channels| (1..=MAX_CHANNEL_COUNT).contains(&channels.count()))
+                        .and_then(|channels| {
+                            codec_params
+                                .sample_rate
+                                .map(|sample_rate| (channels, sample_rate))
+                        })
+                        .and_then(|(channels, sample_rate)| {
+                            SUPPORTED_SAMPLE_RATE_RANGE
+                                .contains(&sample_rate)
+                                .then_some((channels, sample_rate))
                         })
-                        .map(|(track_id, sample_rate, duration, decoder)| {
-                            let resampler_delay_count = resampler.output_delay() as u32;
+                        .map(|(channels, sample_rate)| {
+                            let time_base = codec_params
+                                .time_base
+                                .unwrap_or(TimeBase::new(1, sample_rate));
+
+                            let timeline_length = codec_params.n_frames.map(|frame_count| {
+                                let Time { seconds, frac } = time_base.calc_time(frame_count);
+
+                                seconds as f64 + frac
+                            });
 
-                            Self {
-                                format,
-                                track_id,
+                            (channels, sample_rate, time_base, timeline_length)
+                        })
+                        .and_then(|(channels, sample_rate, time_base, timeline_length)| {
+                            SourceResampler::new(
+                                ResampleQuality::default(),
                                 sample_rate,
-                                output_sample_rate: sample_rate,
-                                duration,
-                                decoder,
-                                resampler,
-                                resampler_delay_count,
-                                has_trimmed_delay: false,
-                                resampler_input_count: 0,
-                                resampler_output_count: 0,
-                                packet_buffer: [VecDeque::new(), VecDeque::new()],
-                                source_buffer: VecDeque::new(),
-                            }
+                                sample_rate,
+                                1_f64,
+                                channels.count(),
+                            )
+                            .map(|resampler| {
+                                (channels, sample_rate, time_base, timeline_length, resampler)
+                            })
                         })
+                        .map(
+                            |(channels, sample_rate, time_base, timeline_length, resampler)| {
+                                let album =
+                                    media_source.find_tag_value(StandardTagKey::Album, &["ALBUM"]);
+
+                                let replay_gain_info = ReplayGainInfo {
+                                    track_gain: media_source.find_replay_gain_value(
+                                        StandardTagKey::ReplayGainTrackGain,
+                                        "REPLAYGAIN_TRACK_GAIN",
+                                    ),
+                                    track_peak: media_source.find_replay_gain_value(
+                                        StandardTagKey::ReplayGainTrackPeak,
+                                        "REPLAYGAIN_TRACK_PEAK",
+                                    ),
+                                    album_gain: media_source.find_replay_gain_value(
+                                        StandardTagKey::ReplayGainAlbumGain,
+                                        "REPLAYGAIN_ALBUM_GAIN",
+                                    ),
+                                    album_peak: media_source.find_replay_gain_value(
+                                        StandardTagKey::ReplayGainAlbumPeak,
+                                        "REPLAYGAIN_ALBUM_PEAK",
+                                    ),
+                                };
+
+                                let resampler_delay_count = resampler.get_delay() as u32;
+
+                                let tag_loop_frame_range = media_source
+                                    .find_custom_tag_value(&["LOOPSTART"])
+                                    .and_then(|value| value.trim().parse::<u64>().ok())
+                                    .and_then(|start| {
+                                        media_source
+                                            .find_custom_tag_value(&["LOOPLENGTH"])
+                                            .and_then(|value| value.trim().parse::<u64>().ok())
+                                            .map(|length| start + length)
+                                            .or_else(|| {
+                                                media_source
+                                                    .find_custom_tag_value(&["LOOPEND"])
+                                                    .and_then(|value| {
+                                                        value.trim().parse::<u64>().ok()
+                                                    })
+                                            })
+                                            .filter(|end| *end > start)
+                                            .map(|end| (start, end))
+                                    });
+
+                                Self {
+                                    serial: SOURCE_SCHEDULER_SERIAL.fetch_add(1, Ordering::Relaxed),
+                                    duration_refinement_is_cancelled: Arc::new(AtomicBool::new(
+                                        false,
+                                    )),
+                                    path: path.clone(),
+                                    media_source,
+                                    time_base,
+                                    sample_rate,
+                                    output_sample_rate: sample_rate,
+                                    timeline_length,
+                                    timeline_position: 0_f64,
+                                    album,
+                                    replay_gain_info,
+                                    resample_quality: ResampleQuality::default(),
+                                    resampler,
+                                    resampler_delay_count,
+                                    has_trimmed_delay: false,
+                                    resampler_input_count: 0,
+                                    resampler_output_count: 0,
+                                    seek_trim_frame_count: 0,
+                                    frame_position: 0,
+                                    tag_loop_frame_range,
+                                    tag_loop_policy: None,
+                                    custom_loop_frame_range: None,
+                                    loop_frame_range: None,
+                                    loop_policy: LoopPolicy::default(),
+                                    loop_remaining_count: None,
+                                    fade_out_frame_count: None,
+                                    has_ended: false,
+                                    channel_count: channels.count(),
+                                    channel_matrix: ChannelMatrix::downmix_to_stereo(channels),
+                                    playback_rate: 1_f64,
+                                    time_stretcher: None,
+                                    packet_buffer: vec![VecDeque::new(); channels.count()],
+                                    source_buffer: VecDeque::new(),
+                                    stretched_buffer: VecDeque::new(),
+                                }
+                            },
+                        )
                         .ok_or(SourceException::InvalidFile)
                 })
         }
     }
 
-    impl<'a> SourceScheduler<'a> {
+    impl SourceScheduler {
         fn append_to_packet_buffer<T>(
             source: &AudioBuffer<T>,
-            target: &mut [VecDeque<f32>; 2],
-        ) -> u32
+            target: &mut [VecDeque<f32>],
+        ) -> Result<u32, SourceException>
         where
             T: Sample + IntoSample<f32>,
         {
-            let zeroth_channel = source.chan(0);
-
-            let first_channel = source.chan(1);
+            if source.spec().channels.count() < target.len() {
+                return Err(SourceException::IncorrectFile);
+            }
 
-            target[0].reserve(zeroth_channel.len());
+            target.iter_mut().enumerate().for_each(|(index, target)| {
+                let channel = source.chan(index);
 
-            target[1].reserve(first_channel.len());
+                target.reserve(channel.len());
 
-            zeroth_channel
-                .iter()
-                .zip(first_channel)
-                .map(|(zeroth, first)| {
-                    target[0].push_back((*zeroth).into_sample());
+                target.extend(channel.iter().map(|sample| (*sample).into_sample()));
+            });
 
-                    target[1].push_back((*first).into_sample());
-                })
-                .count() as u32
+            Ok(source.frames() as u32)
         }
 
         fn request_packet_and_append_to_buffer(
-            format: &mut Box<dyn FormatReader>,
-            track_id: u32,
-            decoder: &mut Box<dyn Decoder>,
-            packet_buffer: &mut [VecDeque<f32>; 2],
+            media_source: &mut MediaSource,
+            packet_buffer: &mut [VecDeque<f32>],
         ) -> Result<u32, SourceException> {
-            loop {
-                let packet = match format.next_packet() {
-                    Ok(packet) => packet,
-                    Err(SymphoniaError::IoError(error))
-                        if error.kind() == IOErrorKind::UnexpectedEof
-                            && error.to_string() == "end of stream" =>
-                    {
-                        return Ok(0);
-                    }
-                    Err(_) => {
-                        return Err(SourceException::IncorrectFile);
-                    }
-                };
+            media_source
+                .request_audio_buffer()
+                .map_err(SourceException::from)
+                .and_then(|decoded| match decoded {
+                    Some(decoded) => match decoded {
+                        AudioBufferRef::U8(source) => {
+                            Self::append_to_packet_buffer(&source, packet_buffer)
+                        }
+                        AudioBufferRef::U16(source) => {
+                            Self::append_to_packet_buffer(&source, packet_buffer)
+                        }
+                        AudioBufferRef::U24(source) => {
+                            Self::append_to_packet_buffer(&source, packet_buffer)
+                        }
+                        AudioBufferRef::U32(source) => {
+                            Self::append_to_packet_buffer(&source, packet_buffer)
+                        }
+                        AudioBufferRef::S8(source) => {
+                            Self::append_to_packet_buffer(&source, packet_buffer)
+                        }
+                        AudioBufferRef::S16(source) => {
+                            Self::append_to_packet_buffer(&source, packet_buffer)
+                        }
+                        AudioBufferRef::S24(source) => {
+                            Self::append_to_packet_buffer(&source, packet_buffer)
+                        }
+                        AudioBufferRef::S32(source) => {
+                            Self::append_to_packet_buffer(&source, packet_buffer)
+                        }
+                        AudioBufferRef::F32(source) => {
+                            Self::append_to_packet_buffer(&source, packet_buffer)
+                        }
+                        AudioBufferRef::F64(source) => {
+                            Self::append_to_packet_buffer(&source, packet_buffer)
+                        }
+                    },
+                    None => Ok(0),
+                })
+        }
 
-                if packet.track_id() != track_id {
-                    continue;
-                }
+        fn drain_packet_buffer(
+            packet_buffer: &mut [VecDeque<f32>],
+            frame_count: usize,
+        ) -> Vec<Vec<f32>> {
+            packet_buffer
+                .iter_mut()
+                .map(|channel| channel.drain(..frame_count).collect::<Vec<f32>>())
+                .collect()
+        }
 
-                match decoder.decode(&packet) {
-                    Ok(decoded) => {
-                        return Ok(match decoded {
-                            AudioBufferRef::U8(source) => {
-                                Self::append_to_packet_buffer(&source, packet_buffer)
-                            }
-                            AudioBufferRef::U16(source) => {
-                                Self::append_to_packet_buffer(&source, packet_buffer)
-                            }
-                            AudioBufferRef::U24(source) => {
-                                Self::append_to_packet_buffer(&source, packet_buffer)
-                            }
-                            AudioBufferRef::U32(source) => {
-                                Self::append_to_packet_buffer(&source, packet_buffer)
-                            }
-                            AudioBufferRef::S8(source) => {
-                                Self::append_to_packet_buffer(&source, packet_buffer)
-                            }
-                            AudioBufferRef::S16(source) => {
-                                Self::append_to_packet_buffer(&source, packet_buffer)
-                            }
-                            AudioBufferRef::S24(source) => {
-                                Self::append_to_packet_buffer(&source, packet_buffer)
-                            }
-                            AudioBufferRef::S32(source) => {
-                                Self::append_to_packet_buffer(&source, packet_buffer)
-                            }
-                            AudioBufferRef::F32(source) => {
-                                Self::append_to_packet_buffer(&source, packet_buffer)
-                            }
-                            AudioBufferRef::F64(source) => {
-                                Self::append_to_packet_buffer(&source, packet_buffer)
-                            }
-                        });
-                    }
-                    Err(SymphoniaError::IoError(_)) | Err(SymphoniaError::DecodeError(_)) => {
-                        continue;
-                    }
-                    Err(_) => {
-                        return Err(SourceException::IncorrectFile);
-                    }
-                }
+        pub fn set_custom_channel_matrix(&mut self, coefficient_matrix: Option<&Vec<Vec<f64>>>) {
+            if let Some(channel_matrix) = coefficient_matrix.and_then(|coefficient_matrix| {
+                ChannelMatrix::from_custom(coefficient_matrix, self.channel_count, 2)
+            }) {
+                self.channel_matrix = channel_matrix;
+            } else {
+                self.channel_matrix = ChannelMatrix::downmix_to_stereo(
+                    self.media_source.codec_params.channels.unwrap_or_default(),
+                );
             }
         }
 
-        fn append_to_source_buffer(source: Vec<Vec<f32>>, target: &mut VecDeque<[f32; 2]>) -> u32 {
-            let mut source_iter = source.into_iter();
-
-            let zeroth_channel = source_iter.next().unwrap_or_default();
-
-            let first_channel = source_iter.next().unwrap_or_default();
-
-            target.reserve(min(zeroth_channel.len(), first_channel.len()));
+        fn get_media_rate_factor(&self) -> f64 {
+            if self.time_stretcher.is_some() {
+                1_f64
+            } else {
+                self.playback_rate
+            }
+        }
 
-            zeroth_channel
-                .into_iter()
-                .zip(first_channel)
-                .map(|(zeroth, first)| {
-                    target.push_back([zeroth, first]);
-                })
-                .count() as u32
+        fn get_resample_ratio(&self) -> f64 {
+            self.output_sample_rate as f64 / self.sample_rate as f64 / self.get_media_rate_factor()
         }
 
         pub fn set_output_sample_rate(&mut self, output_sample_rate: u32) {
-            self.output_sample_rate = output_sample_rate;
+            if output_sample_rate == self.output_sample_rate {
+                return;
+            }
 
-            let _ = self.resampler.set_resample_ratio(
-                self.output_sample_rate as f64 / self.sample_rate as f64,
-                false,
-            );
+            self.output_sample_rate = output_sample_rate;
 
-            self.resampler_delay_count = self.resampler.output_delay() as u32;
+            self.set_playback_rate(self.playback_rate, self.time_stretcher.is_some());
         }
 
-        pub fn get_duration(&self) -> u32 {
-            self.duration
+        pub fn determine_playback_rate_seek_necessity(
+            &self,
+            playback_rate: f64,
+            pitch_is_preserved: bool,
+        ) -> bool {
+            if self.time_stretcher.is_some() {
+                !pitch_is_preserved
+            } else {
+                pitch_is_preserved && playback_rate != 1_f64 && self.playback_rate != 1_f64
+            }
         }
 
-        pub fn request_source_stream(
+        pub fn adjust_playback_rate(
+            &mut self,
+            playback_rate: f64,
+            pitch_is_preserved: bool,
+        ) -> bool {
+            if self.determine_playback_rate_seek_necessity(playback_rate, pitch_is_preserved) {
+                return false;
+            }
+
+            match self.time_stretcher.as_mut() {
+                Some(time_stretcher) if playback_rate != 1_f64 => {
+                    time_stretcher.set_rate(playback_rate);
+                }
+                Some(_) => {
+                    if let Some(mut time_stretcher) = self.time_stretcher.take() {
+                        time_stretcher.flush(&mut self.stretched_buffer);
+                    }
+                }
+                None if pitch_is_preserved && playback_rate != 1_f64 => {
+                    self.time_stretcher =
+                        Some(TimeStretcher::new(playback_rate, self.output_sample_rate));
+                }
+                None if playback_rate != self.playback_rate => {
+                    let previous_resample_ratio = self.get_resample_ratio();
+
+                    self.playback_rate = playback_rate;
+
+                    if self
+                        .resampler
+                        .set_media_rate_factor(self.get_media_rate_factor())
+                    {
+                        self.resampler_input_count = (self.resampler_input_count as f64
+                            * previous_resample_ratio
+                            / self.get_resample_ratio())
+                        .round() as u32;
+                    } else {
+                        self.stretched_buffer.extend(self.source_buffer.drain(..));
+
+                        self.refresh_resampler();
+                    }
+                }
+                None => {}
+            }
+
+            self.playback_rate = playback_rate;
+
+            true
+        }
+
+        pub fn set_playback_rate(&mut self, playback_rate: f64, pitch_is_preserved: bool) {
+            self.playback_rate = playback_rate;
+
+            self.time_stretcher = (pitch_is_preserved && playback_rate != 1_f64)
+                .then(|| TimeStretcher::new(playback_rate, self.output_sample_rate));
+
+            self.stretched_buffer = VecDeque::new();
+
+            self.refresh_resampler();
+        }
+
+        pub fn set_resample_quality(&mut self, resample_quality: ResampleQuality) {
+            self.resample_quality = resample_quality;
+
+            self.refresh_resampler();
+        }
+
+        fn refresh_resampler(&mut self) {
+            if let Some(resampler) = SourceResampler::new(
+                self.resample_quality,
+                self.sample_rate,
+                self.output_sample_rate,
+                self.get_media_rate_factor(),
+                self.channel_count,
+            ) {
+                self.resampler = resampler;
+
+                self.resampler_delay_count = self.resampler.get_delay() as u32;
+
+                self.has_trimmed_delay = false;
+
+                self.resampler_input_count = 0;
+
+                self.resampler_output_count = 0;
+
+                self.source_buffer = VecDeque::new();
+            }
+        }
+
+        fn get_frame_second(&self, frame: u64) -> f64 {
+            frame as f64 / self.sample_rate as f64
+        }
+
+        fn reset_loop_state(&mut self) {
+            self.loop_remaining_count = self.loop_policy.get_count();
+
+            self.fade_out_frame_count = None;
+
+            self.has_ended = false;
+        }
+
+        fn refresh_loop(&mut self) {
+            (self.loop_frame_range, self.loop_policy) = match (
+                self.custom_loop_frame_range,
+                self.tag_loop_frame_range.zip(self.tag_loop_policy),
+            ) {
+                (Some(custom_loop_frame_range), _) => {
+                    (Some(custom_loop_frame_range), LoopPolicy::default())
+                }
+                (None, Some((tag_loop_frame_range, tag_loop_policy))) => {
+                    (Some(tag_loop_frame_range), tag_loop_policy)
+                }
+                (None, None) => (None, LoopPolicy::default()),
+            };
+
+            self.reset_loop_state();
+        }
+
+        pub fn set_tag_loop(&mut self, tag_loop_policy: Option<LoopPolicy>) {
+            self.tag_loop_policy = tag_loop_policy;
+
+            self.refresh_loop();
+        }
+
+        pub fn determine_loop_seek_necessity(
+            &self,
+            loop_range: Option<(f64, f64)>,
+            progress: f64,
+        ) -> bool {
+            loop_range.is_some_and(|(start, end)| {
+                progress < start
+                    || progress >= end
+                    || self.frame_position >= (end * self.sample_rate as f64).round() as u64
+            })
+        }
+
+        pub fn set_custom_loop(&mut self, loop_range: Option<(f64, f64)>) -> bool {
+            self.custom_loop_frame_range = loop_range.map(|(start, end)| {
+                (
+                    (start * self.sample_rate as f64).round() as u64,
+                    (end * self.sample_rate as f64).round() as u64,
+                )
+            });
+
+            self.refresh_loop();
+
+            self.loop_frame_range
+                .is_some_and(|(_, end)| self.frame_position >= end)
+        }
+
+        pub fn fold_progress(&self, timeline_anchor: f64, progress: f64) -> f64 {
+            match self.loop_frame_range {
+                Some((start, end))
+                    if timeline_anchor.max(0_f64) < self.get_frame_second(end)
+                        && progress >= self.get_frame_second(end) =>
+                {
+                    let loop_length = self.get_frame_second(end - start);
+
+                    let excess = progress - self.get_frame_second(end);
+
+                    match self.loop_policy.get_count() {
+                        Some(count)
+                            if self.loop_policy.get_fade_duration() == 0_f64
+                                && (excess / loop_length).floor() as u64 >= count as u64 =>
+                        {
+                            progress - count as f64 * loop_length
+                        }
+                        _ => self.get_frame_second(start) + excess % loop_length,
+                    }
+                }
+                _ => progress,
+            }
+        }
+
+        fn wrap_loop(&mut self, previous_frame_position: u64) -> Result<(), SourceException> {
+            let Some((start, end)) = self.loop_frame_range else {
+                return Ok(());
+            };
+
+            if !(previous_frame_position < end
+                && self.frame_position >= end
+                && (self.loop_remaining_count != Some(0) || self.fade_out_frame_count.is_some()))
+            {
+                return Ok(());
+            }
+
+            let overflow_frame_count = (self.frame_position - end) as usize;
+
+            self.packet_buffer.iter_mut().for_each(|channel| {
+                channel.truncate(channel.len().saturating_sub(overflow_frame_count));
+            });
+
+            if let Some(loop_remaining_count) = self
+                .loop_remaining_count
+                .as_mut()
+                .filter(|loop_remaining_count| **loop_remaining_count > 0)
+            {
+                *loop_remaining_count -= 1;
+
+                if *loop_remaining_count == 0 && self.loop_policy.get_fade_duration() > 0_f64 {
+                    self.fade_out_frame_count = Some((
+                        0,
+                        ((self.loop_policy.get_fade_duration() * self.output_sample_rate as f64
+                            / self.get_media_rate_factor())
+                        .round() as u64)
+                            .max(1),
+                    ));
+                }
+            }
+
+            self.seek_media_source(SeekTo::TimeStamp {
+                ts: self.time_base.calc_timestamp(Time::new(
+                    start / self.sample_rate as u64,
+                    (start % self.sample_rate as u64) as f64 / self.sample_rate as f64,
+                )),
+                track_id: self.media_source.track_id,
+            })
+            .map(|_| {
+                self.timeline_position -= self.get_frame_second(end - start);
+            })
+        }
+
+        pub fn get_duration(&self) -> Option<f64> {
+            self.timeline_length
+        }
+
+        pub fn request_duration_refinement(&self, tx: &mpsc::Sender<Command>) {
+            if self.timeline_length.is_none() {
+                let serial = self.serial;
+
+                let path = self.path.clone();
+
+                let time_base = self.time_base;
+
+                let tx = tx.clone();
+
+                let is_cancelled = self.duration_refinement_is_cancelled.clone();
+
+                thread::spawn(move || {
+                    if let Some(frame_count) =
+                        MediaSource::scan_frame_count(&path, Some(&is_cancelled))
+                    {
+                        let Time { seconds, frac } = time_base.calc_time(frame_count);
+
+                        let _ = tx.send(Command::CorrectDuration(serial, seconds as f64 + frac));
+                    }
+                });
+            }
+        }
+
+        pub fn correct_duration(&mut self, serial: u64, timeline_length: f64) -> bool {
+            if self.serial == serial && self.timeline_length.is_none() {
+                self.timeline_length = Some(timeline_length);
+
+                true
+            } else {
+                false
+            }
+        }
+
+        pub fn get_remaining_duration(&self) -> f64 {
+            if let Some((elapsed_frame_count, fade_out_frame_count)) = self.fade_out_frame_count {
+                (fade_out_frame_count - elapsed_frame_count) as f64 * self.get_media_rate_factor()
+                    / self.output_sample_rate as f64
+            } else if self.loop_remaining_count != Some(0)
+                && self
+                    .loop_frame_range
+                    .is_some_and(|(_, end)| self.frame_position < end)
+            {
+                f64::INFINITY
+            } else {
+                self.timeline_length
+                    .map_or(f64::INFINITY, |timeline_length| {
+                        (timeline_length - self.timeline_position).max(0_f64)
+                    })
+            }
+        }
+
+        pub fn get_replay_gain_info(&self) -> &ReplayGainInfo {
+            &self.replay_gain_info
+        }
+
+        pub fn determine_same_album(&self, other: &Self) -> bool {
+            self.album.is_some() && self.album == other.album
+        }
+
+        pub fn request_source_stream(
+            &mut self,
+            expected_frame_count: u32,
+        ) -> Result<Option<SourceStream>, SourceException> {
+            if self.time_stretcher.is_none() {
+                if self.stretched_buffer.is_empty() {
+                    return self.request_media_stream(expected_frame_count);
+                }
+
+                let mut source: Vec<[f32; 2]> = self
+                    .stretched_buffer
+                    .drain(
+                        ..self
+                            .stretched_buffer
+                            .len()
+                            .min(expected_frame_count as usize),
+                    )
+                    .collect();
+
+                let remaining_frame_count = expected_frame_count - source.len() as u32;
+
+                if remaining_frame_count == 0 {
+                    return Ok(Some(SourceStream::Continue(source)));
+                }
+
+                return self
+                    .request_media_stream(remaining_frame_count)
+                    .map(|media_stream| match media_stream {
+                        Some(SourceStream::Continue(media_source)) => {
+                            source.extend(media_source);
+
+                            Some(SourceStream::Continue(source))
+                        }
+                        Some(SourceStream::Break(media_source)) => {
+                            source.extend(media_source);
+
+                            Some(SourceStream::Break(source))
+                        }
+                        None => Some(SourceStream::Break(source)),
+                    });
+            }
+
+            loop {
+                if self.stretched_buffer.len() >= expected_frame_count as usize {
+                    break Ok(Some(SourceStream::Continue(
+                        self.stretched_buffer
+                            .drain(..expected_frame_count as usize)
+                            .collect(),
+                    )));
+                }
+
+                let media_stream = self.request_media_stream(
+                    ((expected_frame_count as f64 * self.playback_rate).ceil() as u32).max(1),
+                )?;
+
+                if let Some(time_stretcher) = self.time_stretcher.as_mut() {
+                    match media_stream {
+                        Some(SourceStream::Continue(source)) => {
+                            time_stretcher.process(&source, &mut self.stretched_buffer);
+                        }
+                        media_stream => {
+                            if let Some(SourceStream::Break(source)) = media_stream {
+                                time_stretcher.process(&source, &mut self.stretched_buffer);
+                            }
+
+                            time_stretcher.flush(&mut self.stretched_buffer);
+
+                            break Ok((!self.stretched_buffer.is_empty()).then(|| {
+                                SourceStream::Break(self.stretched_buffer.drain(..).collect())
+                            }));
+                        }
+                    }
+                }
+            }
+        }
+
+        fn request_media_stream(
             &mut self,
             expected_frame_count: u32,
         ) -> Result<Option<SourceStream>, SourceException> {
+            if self.has_ended {
+                return Ok(None);
+            }
+
+            let resample_ratio = self.get_resample_ratio();
+
             let expected_frame_count = if self.has_trimmed_delay {
                 expected_frame_count
             } else {
@@ -1213,39 +2374,55 @@ mod scheduler {
                             .collect(),
                     );
                 } else {
-                    let wave_in_count = self.resampler.input_frames_next();
+                    let wave_in_count = self.resampler.get_input_frame_count();
 
                     loop {
-                        if self.packet_buffer[1].len() >= wave_in_count {
+                        if self.packet_buffer[0].len() >= wave_in_count {
                             self.resampler_input_count += wave_in_count as u32;
 
-                            if let Ok(source) = self.resampler.process(
-                                &[
-                                    self.packet_buffer[0]
-                                        .drain(..wave_in_count)
-                                        .collect::<Vec<f32>>(),
-                                    self.packet_buffer[1]
-                                        .drain(..wave_in_count)
-                                        .collect::<Vec<f32>>(),
-                                ],
-                                None,
-                            ) {
+                            if let Ok(source) = self.resampler.process(Self::drain_packet_buffer(
+                                &mut self.packet_buffer,
+                                wave_in_count,
+                            )) {
                                 self.resampler_output_count +=
-                                    Self::append_to_source_buffer(source, &mut self.source_buffer);
+                                    self.channel_matrix.apply(source, &mut self.source_buffer);
                             } else {
                                 return Err(SourceException::IncorrectFile);
                             }
 
                             continue 'entry;
                         } else {
+                            let previous_packet_buffer_count = self.packet_buffer[0].len();
+
                             match Self::request_packet_and_append_to_buffer(
-                                &mut self.format,
-                                self.track_id,
-                                &mut self.decoder,
+                                &mut self.media_source,
                                 &mut self.packet_buffer,
                             ) {
                                 Ok(appended_packet_buffer_count) => {
                                     if appended_packet_buffer_count > 0 {
+                                        let trimmed_frame_count = self
+                                            .seek_trim_frame_count
+                                            .min(appended_packet_buffer_count as usize);
+
+                                        self.packet_buffer.iter_mut().for_each(|channel| {
+                                            channel.drain(
+                                                previous_packet_buffer_count
+                                                    ..previous_packet_buffer_count
+                                                        + trimmed_frame_count,
+                                            );
+                                        });
+
+                                        self.seek_trim_frame_count -= trimmed_frame_count;
+
+                                        let previous_frame_position = self.frame_position;
+
+                                        self.frame_position += (appended_packet_buffer_count
+                                            as usize)
+                                            .saturating_sub(trimmed_frame_count)
+                                            as u64;
+
+                                        self.wrap_loop(previous_frame_position)?;
+
                                         continue;
                                     } else {
                                         break;
@@ -1258,26 +2435,32 @@ mod scheduler {
                         }
                     }
 
-                    let packet_buffer_count = self.packet_buffer[1].len();
+                    let packet_buffer_count = self.packet_buffer[0].len();
 
                     if packet_buffer_count > 0 {
                         self.resampler_input_count += packet_buffer_count as u32;
 
-                        if let Ok(source) = self
-                            .resampler
-                            .set_chunk_size(packet_buffer_count)
-                            .and_then(|_| {
-                                self.resampler.process(
-                                    &[
-                                        self.packet_buffer[0].drain(..).collect::<Vec<f32>>(),
-                                        self.packet_buffer[1].drain(..).collect::<Vec<f32>>(),
-                                    ],
-                                    None,
-                                )
-                            })
+                        if let Ok(source) =
+                            self.resampler
+                                .process_partial(Some(Self::drain_packet_buffer(
+                                    &mut self.packet_buffer,
+                                    packet_buffer_count,
+                                )))
                         {
                             self.resampler_output_count +=
-                                Self::append_to_source_buffer(source, &mut self.source_buffer);
+                                self.channel_matrix.apply(source, &mut self.source_buffer);
+
+                            let padded_frame_count = (self.resampler_output_count as usize)
+                                .saturating_sub(
+                                    self.resampler_delay_count as usize
+                                        + (self.resampler_input_count as f64 * resample_ratio)
+                                            .floor()
+                                            as usize,
+                                );
+
+                            self.source_buffer.truncate(
+                                self.source_buffer.len().saturating_sub(padded_frame_count),
+                            );
                         } else {
                             return Err(SourceException::IncorrectFile);
                         }
@@ -1285,29 +2468,23 @@ mod scheduler {
                         continue 'entry;
                     }
 
-                    if ((self.resampler_output_count - self.resampler_delay_count) as u64
-                        * self.sample_rate as u64)
-                        < (self.resampler_input_count as u64 * self.output_sample_rate as u64)
+                    if ((self.resampler_output_count - self.resampler_delay_count) as f64)
+                        < (self.resampler_input_count as f64 * resample_ratio).floor()
                     {
                         loop {
-                            if let Ok(source) =
-                                self.resampler.process_partial::<Vec<f32>>(None, None)
-                            {
+                            if let Ok(source) = self.resampler.process_partial(None) {
                                 self.resampler_output_count +=
-                                    Self::append_to_source_buffer(source, &mut self.source_buffer);
+                                    self.channel_matrix.apply(source, &mut self.source_buffer);
                             } else {
                                 return Err(SourceException::IncorrectFile);
                             }
 
-                            if ((self.resampler_output_count - self.resampler_delay_count) as u64
-                                * self.sample_rate as u64)
-                                >= (self.resampler_input_count as u64
-                                    * self.output_sample_rate as u64)
+                            if ((self.resampler_output_count - self.resampler_delay_count) as f64)
+                                >= (self.resampler_input_count as f64 * resample_ratio).floor()
                             {
                                 self.source_buffer.truncate(
                                     self.source_buffer.len()
-                                        + (self.output_sample_rate as f32 / self.sample_rate as f32
-                                            * self.resampler_input_count as f32)
+                                        + (self.resampler_input_count as f64 * resample_ratio)
                                             .floor()
                                             as usize
                                         + self.resampler_delay_count as usize
@@ -1335,55 +2512,107 @@ mod scheduler {
                     SourceStream::Break(ref mut source_stream) => source_stream,
                 };
 
-                if source_stream.len() > self.resampler_delay_count as usize {
+                if source_stream.len() > self.resampler_delay_count as usize
+                    || self.resampler_delay_count == 0
+                {
                     let _ = source_stream.drain(..self.resampler_delay_count as usize);
                 } else {
                     return Err(SourceException::IncorrectFile);
                 }
             }
 
+            if let Some((elapsed_frame_count, fade_out_frame_count)) =
+                self.fade_out_frame_count.as_mut()
+            {
+                let (SourceStream::Continue(frames) | SourceStream::Break(frames)) =
+                    &mut source_stream;
+
+                frames.truncate((*fade_out_frame_count - *elapsed_frame_count) as usize);
+
+                frames.iter_mut().enumerate().for_each(|(index, frame)| {
+                    let gain = 1_f32
+                        - (*elapsed_frame_count + index as u64) as f32
+                            / *fade_out_frame_count as f32;
+
+                    *frame = frame.map(|sample| sample * gain);
+                });
+
+                *elapsed_frame_count += frames.len() as u64;
+
+                if *elapsed_frame_count >= *fade_out_frame_count {
+                    self.has_ended = true;
+
+                    source_stream = SourceStream::Break(mem::take(frames));
+                }
+            }
+
+            self.timeline_position += SourceStream::len(Some(&source_stream)) as f64
+                * self.get_media_rate_factor()
+                / self.output_sample_rate as f64;
+
             Ok(Some(source_stream))
         }
 
-        pub fn seek(
-            &mut self,
-            second: u32,
-            fraction: Option<f64>,
-        ) -> Result<(u32, f64), SourceException> {
-            self.format
-                .seek(
-                    SeekMode::Accurate,
-                    SeekTo::Time {
-                        time: Time::new(second as u64, fraction.unwrap_or(0_f64)),
-                        track_id: Some(self.track_id),
+        fn seek_media_source(&mut self, seek_to: SeekTo) -> Result<f64, SourceException> {
+            self.media_source
+                .format
+                .seek(SeekMode::Accurate, seek_to)
+                .map_err(|_| SourceException::IncorrectFile)
+                .map(
+                    |SeekedTo {
+                         actual_ts,
+                         required_ts,
+                         ..
+                     }| {
+                        self.media_source.decoder.reset();
+
+                        let Time { seconds, frac } = self
+                            .time_base
+                            .calc_time(required_ts.saturating_sub(actual_ts));
+
+                        self.seek_trim_frame_count =
+                            ((seconds as f64 + frac) * self.sample_rate as f64).round() as usize;
+
+                        let Time { seconds, frac } =
+                            self.time_base.calc_time(required_ts.max(actual_ts));
+
+                        let position = seconds as f64 + frac;
+
+                        self.frame_position = (position * self.sample_rate as f64).round() as u64;
+
+                        position
                     },
                 )
-                .map_err(|_| SourceException::IncorrectFile)
-                .map(|SeekedTo { actual_ts, .. }| {
-                    self.decoder.reset();
+        }
 
-                    self.resampler.reset();
+        pub fn seek(&mut self, second: f64) -> Result<f64, SourceException> {
+            self.seek_media_source(SeekTo::Time {
+                time: Time::new(second.trunc() as u64, second.fract()),
+                track_id: Some(self.media_source.track_id),
+            })
+            .inspect(|position| {
+                self.resampler.reset();
 
-                    self.has_trimmed_delay = false;
+                self.has_trimmed_delay = false;
 
-                    self.resampler_input_count = 0;
+                self.resampler_input_count = 0;
 
-                    self.resampler_output_count = 0;
+                self.resampler_output_count = 0;
 
-                    self.packet_buffer = [VecDeque::new(), VecDeque::new()];
+                self.packet_buffer = vec![VecDeque::new(); self.channel_count];
 
-                    self.source_buffer = VecDeque::new();
+                self.source_buffer = VecDeque::new();
 
-                    self.decoder
-                        .codec_params()
-                        .time_base
-                        .map(|time_base| {
-                            let Time { seconds, frac } = time_base.calc_time(actual_ts);
+                if let Some(time_stretcher) = self.time_stretcher.as_mut() {
+                    time_stretcher.reset();
+                }
 
-                            (seconds as u32, frac)
-                        })
-                        .unwrap_or((second, fraction.unwrap_or(0_f64)))
-                })
+                self.stretched_buffer = VecDeque::new();
+
+                self.reset_loop_state();
+
+                self.timeline_position = *position;
+            })
         }
 
         pub fn determine_additional_seek_necessity(&self) -> bool {
@@ -1406,6 +2635,49 @@ fn run_player_loop(
 
     let mut volume = Volume::new(1_f64);
 
+    let mut volume_curve = VolumeCurve::default();
+
+    let mut ramp_duration = RampDuration::default();
+
+    let mut gain_ramp = GainRamp::new(volume.get(), 48_000);
+
+    let mut deferred_command_vector = Vec::<Command>::new();
+
+    let mut crossfade = Crossfade::new(0_f64, CrossfadeCurve::EqualPower);
+
+    let mut replay_gain = ReplayGain::new(ReplayGainMode::Off, 0_f64, true);
+
+    let mut custom_channel_matrix = Option::<Vec<Vec<f64>>>::None;
+
+    let mut upmix_is_enabled = false;
+
+    let mut stereo_image = StereoImage::default();
+
+    let mut dither_mode = DitherMode::None;
+
+    let mut limiter_ceiling = Option::<f64>::None;
+
+    let mut reported_gain_reduction = (0_f64, 0_f64);
+
+    let mut playback_rate = 1_f64;
+
+    let mut pitch_is_preserved = true;
+
+    let mut resample_quality = ResampleQuality::default();
+
+    let mut tag_loop_policy = Option::<LoopPolicy>::None;
+
+    let mut pending_loop_range = Option::<(f64, f64)>::None;
+
+    let mut dsp_chain = DspChain::default();
+
+    let mut crossfeed_config_map = HashMap::<String, CrossfeedConfig>::new();
+
+    let mut analysis_config = Option::<AnalysisConfig>::None;
+
+    let mut analysis_handler =
+        Option::<ThreadsafeFunction<String, ErrorStrategy::CalleeHandled>>::None;
+
     let mut identifier = None;
 
     let mut timeline_anchor = 0_f64;
@@ -1419,44 +2691,25 @@ fn run_player_loop(
     }
     .into();
 
+    let duration_refinement_tx = background_event_tx.clone();
+
     let audio_session_events: IAudioSessionEvents = AudioSessionEvents {
         tx: background_event_tx,
     }
     .into();
 
-    if let Ok((device_enumerator, event_handle, mut resampler)) =
-        DeviceEnumerator::init(&notification_client)
-            .and_then(|device_enumerator| {
-                unsafe { CreateEventW(None, false, false, None) }
-                    .map_err(|_| DeviceException::FatalException)
-                    .map(|event_handle| (device_enumerator, event_handle))
-            })
-            .and_then(|(device_enumerator, event_handle)| {
-                SincFixedIn::<f32>::new(
-                    1_f64,
-                    5_f64,
-                    SincInterpolationParameters {
-                        sinc_len: 256,
-                        f_cutoff: 0.95,
-                        oversampling_factor: 256,
-                        interpolation: SincInterpolationType::Linear,
-                        window: WindowFunction::BlackmanHarris2,
-                    },
-                    1024,
-                    2,
-                )
+    if let Ok((device_enumerator, event_handle)) = DeviceEnumerator::init(&notification_client)
+        .and_then(|device_enumerator| {
+            unsafe { CreateEventW(None, false, false, None) }
                 .map_err(|_| DeviceException::FatalException)
-                .inspect_err(|_| {
-                    let _ = unsafe { CloseHandle(event_handle) };
-                })
-                .map(|resampler| (device_enumerator, event_handle, resampler))
-            })
-            .inspect_err(|_| {
-                active_action_handler.call(
-                    Ok((String::from("initialization"), Some(String::from("false")))),
-                    ThreadsafeFunctionCallMode::Blocking,
-                );
-            })
+                .map(|event_handle| (device_enumerator, event_handle))
+        })
+        .inspect_err(|_| {
+            active_action_handler.call(
+                Ok((String::from("initialization"), Some(String::from("false")))),
+                ThreadsafeFunctionCallMode::Blocking,
+            );
+        })
     {
         active_action_handler.call(
             Ok((String::from("initialization"), Some(String::from("true")))),
@@ -1467,29 +2720,178 @@ fn run_player_loop(
 
         let mut source_scheduler = Option::<SourceScheduler>::None;
 
-        'entry: loop {
-            for command in command_scheduler.backlog_into_iter(
-                !device_scheduler
-                    .as_ref()
-                    .and_then(|device_scheduler| device_scheduler.as_ref().ok())
-                    .is_some_and(|device_scheduler| device_scheduler.get_has_started()),
-            ) {
-                let mut device_scheduler_is_update_with_sample_rate = None;
+        let mut queued_source_scheduler = Option::<(SourceScheduler, String)>::None;
 
-                let mut source_scheduler_is_update = false;
+        let mut track_transition_with_boundary = Option::<(f64, String)>::None;
 
-                let mut pause_is_necessary = false;
+        let mut fading_source_scheduler = Option::<(SourceScheduler, u64, u64)>::None;
 
-                let mut seek_is_necessary_with_second = None;
+        'entry: loop {
+            let device_scheduler_has_started = device_scheduler
+                .as_ref()
+                .and_then(|device_scheduler| device_scheduler.as_ref().ok())
+                .is_some_and(|device_scheduler| device_scheduler.get_has_started());
+
+            let ready_command_vector = if !deferred_command_vector.is_empty()
+                && (gain_ramp.get_is_silent()
+                    || !(is_playing && source_scheduler.is_some() && device_scheduler_has_started))
+            {
+                if device_scheduler_has_started
+                    && let Some(Ok(device_scheduler)) = device_scheduler.as_mut()
+                {
+                    let _ = device_scheduler.drain();
+                }
 
-                let mut source_scheduler_is_incorrect_with_error = None;
+                CommandScheduler::filter(mem::take(&mut deferred_command_vector))
+            } else {
+                VecDeque::new()
+            };
 
-                let mut timeline_anchor_is_update = false;
+            for command in
+                ready_command_vector
+                    .into_iter()
+                    .chain(command_scheduler.backlog_into_iter(
+                        !device_scheduler_has_started && deferred_command_vector.is_empty(),
+                    ))
+            {
+                if !deferred_command_vector.is_empty()
+                    || (match &command {
+                        Command::SelectTrack(_, _)
+                        | Command::ClearTrack
+                        | Command::Seek(_)
+                        | Command::Pause
+                        | Command::Stop
+                        | Command::ModifyResampleQuality(_) => true,
+                        Command::ModifyPlaybackRate(playback_rate, pitch_is_preserved) => {
+                            source_scheduler.as_ref().is_some_and(|source_scheduler| {
+                                source_scheduler.determine_playback_rate_seek_necessity(
+                                    *playback_rate,
+                                    *pitch_is_preserved,
+                                )
+                            })
+                        }
+                        Command::ModifyLoop(loop_range) => {
+                            source_scheduler.as_ref().is_some_and(|source_scheduler| {
+                                source_scheduler
+                                    .determine_loop_seek_necessity(*loop_range, progress)
+                            })
+                        }
+                        _ => false,
+                    } && ramp_duration.get_fade_out() > 0_f64
+                        && is_playing
+                        && source_scheduler.is_some()
+                        && device_scheduler_has_started
+                        && !gain_ramp.get_is_silent())
+                {
+                    if deferred_command_vector.is_empty() {
+                        gain_ramp.ramp_to(0_f32, ramp_duration.get_fade_out());
+                    }
+
+                    deferred_command_vector.push(command);
+
+                    continue;
+                }
+
+                let mut device_scheduler_is_update_with_sample_rate = None;
+
+                let mut source_scheduler_is_update = false;
+
+                let mut pause_is_necessary = false;
+
+                let mut seek_is_necessary_with_second = None;
+
+                let mut source_scheduler_is_incorrect_with_error = None;
+
+                let mut timeline_anchor_is_update = false;
 
                 let mut playback_state_is_update = false;
 
+                let timeline_is_interrupted = !matches!(
+                    command,
+                    Command::ModifyVolume(_)
+                        | Command::ModifyVolumeCurve(_)
+                        | Command::ModifyRampDuration(_)
+                        | Command::ModifyCrossfade(_)
+                        | Command::ModifyReplayGain(_)
+                        | Command::ModifyChannelMatrix(_)
+                        | Command::ModifyUpmix(_)
+                        | Command::ModifyStereoImage(_)
+                        | Command::ModifyDither(_)
+                        | Command::ModifyLimiter(_)
+                        | Command::ModifyResampleQuality(_)
+                        | Command::ModifyLoop(_)
+                        | Command::ModifyTagLoop(_)
+                        | Command::ModifyDspChain(_)
+                        | Command::ModifyDspStage(_)
+                        | Command::ModifyDspBypass(_)
+                        | Command::ModifyAnalysis(_)
+                        | Command::CorrectDuration(_, _)
+                        | Command::QueueTrack(_, _)
+                        | Command::ClearQueuedTrack
+                        | Command::Play
+                        | Command::Pause
+                        | Command::Close
+                );
+
+                if timeline_is_interrupted {
+                    fading_source_scheduler = None;
+
+                    dsp_chain.reset();
+                }
+
+                if let Some((_, new_identifier)) =
+                    track_transition_with_boundary.take_if(|_| timeline_is_interrupted)
+                {
+                    if !matches!(command, Command::SelectTrack(_, _) | Command::ClearTrack) {
+                        active_action_handler.call(
+                            Ok((String::from("finish"), None)),
+                            ThreadsafeFunctionCallMode::Blocking,
+                        );
+
+                        active_action_handler.call(
+                            Ok((
+                                String::from("track"),
+                                Some(format!(
+                                    "{{\"identifier\":{},\"gain\":{}}}",
+                                    to_json_string(&new_identifier),
+                                    source_scheduler
+                                        .as_ref()
+                                        .and_then(|source_scheduler| replay_gain
+                                            .get_gain(source_scheduler.get_replay_gain_info()))
+                                        .map_or(String::from("null"), to_json_number)
+                                )),
+                            )),
+                            ThreadsafeFunctionCallMode::Blocking,
+                        );
+
+                        if let Some(source_scheduler) = source_scheduler.as_ref() {
+                            active_action_handler.call(
+                                Ok((
+                                    String::from("duration"),
+                                    Some(
+                                        source_scheduler
+                                            .get_duration()
+                                            .map_or(String::from("null"), |duration| {
+                                                format!("{:.3}", duration)
+                                            }),
+                                    ),
+                                )),
+                                ThreadsafeFunctionCallMode::Blocking,
+                            );
+                        }
+
+                        identifier = Some(new_identifier);
+                    }
+
+                    timeline_anchor = 0_f64;
+
+                    progress = timeline_anchor;
+
+                    timeline_anchor_is_update = true;
+                }
+
                 match command {
-                    Command::SelectMode(new_audio_endpoint, new_volume) => {
+                    Command::SelectMode(new_audio_endpoint, new_volume, new_stereo_image) => {
                         audio_endpoint_is_default = match new_audio_endpoint {
                             AudioEndpoint::Default => true,
                             AudioEndpoint::Custom(_) => false,
@@ -1497,13 +2899,42 @@ fn run_player_loop(
 
                         volume = new_volume;
 
+                        volume.apply_curve(&volume_curve);
+
+                        gain_ramp.set_gain(volume.get());
+
+                        stereo_image = new_stereo_image;
+
+                        active_action_handler.call(
+                            Ok((String::from("volume"), Some(volume.to_json()))),
+                            ThreadsafeFunctionCallMode::Blocking,
+                        );
+
                         match DeviceScheduler::try_from((
                             &new_audio_endpoint,
                             device_enumerator.get(),
                             &event_handle,
                             &audio_session_events,
                         )) {
-                            Ok(new_device_scheduler) => {
+                            Ok(mut new_device_scheduler) => {
+                                new_device_scheduler.set_upmix(upmix_is_enabled);
+
+                                new_device_scheduler.set_stereo_image(stereo_image);
+
+                                new_device_scheduler.set_dither(dither_mode);
+
+                                new_device_scheduler.set_limiter(
+                                    limiter_ceiling.filter(|_| !dsp_chain.get_bypass()),
+                                );
+
+                                new_device_scheduler.set_analysis(analysis_config);
+
+                                dsp_chain.configure(DspStageConfig::Crossfeed(
+                                    crossfeed_config_map
+                                        .get(new_device_scheduler.get_endpoint_id())
+                                        .copied(),
+                                ));
+
                                 device_scheduler_is_update_with_sample_rate =
                                     Some(new_device_scheduler.get_sample_rate());
 
@@ -1533,7 +2964,25 @@ fn run_player_loop(
                                 &event_handle,
                                 &audio_session_events,
                             )) {
-                                Ok(new_device_scheduler) => {
+                                Ok(mut new_device_scheduler) => {
+                                    new_device_scheduler.set_upmix(upmix_is_enabled);
+
+                                    new_device_scheduler.set_stereo_image(stereo_image);
+
+                                    new_device_scheduler.set_dither(dither_mode);
+
+                                    new_device_scheduler.set_limiter(
+                                        limiter_ceiling.filter(|_| !dsp_chain.get_bypass()),
+                                    );
+
+                                    new_device_scheduler.set_analysis(analysis_config);
+
+                                    dsp_chain.configure(DspStageConfig::Crossfeed(
+                                        crossfeed_config_map
+                                            .get(new_device_scheduler.get_endpoint_id())
+                                            .copied(),
+                                    ));
+
                                     device_scheduler_is_update_with_sample_rate =
                                         Some(new_device_scheduler.get_sample_rate());
 
@@ -1614,14 +3063,279 @@ fn run_player_loop(
                     }
                     Command::ModifyVolume(new_volume) => {
                         volume = new_volume;
+
+                        volume.apply_curve(&volume_curve);
+
+                        gain_ramp.ramp_to(volume.get(), ramp_duration.get_volume());
+
+                        active_action_handler.call(
+                            Ok((String::from("volume"), Some(volume.to_json()))),
+                            ThreadsafeFunctionCallMode::Blocking,
+                        );
+                    }
+                    Command::ModifyVolumeCurve(new_volume_curve) => {
+                        volume_curve = new_volume_curve;
+
+                        volume.apply_curve(&volume_curve);
+
+                        gain_ramp.ramp_to(volume.get(), ramp_duration.get_volume());
+
+                        active_action_handler.call(
+                            Ok((String::from("volume"), Some(volume.to_json()))),
+                            ThreadsafeFunctionCallMode::Blocking,
+                        );
+                    }
+                    Command::ModifyRampDuration(new_ramp_duration) => {
+                        ramp_duration = new_ramp_duration;
+                    }
+                    Command::ModifyCrossfade(new_crossfade) => {
+                        crossfade = new_crossfade;
+                    }
+                    Command::ModifyReplayGain(new_replay_gain) => {
+                        replay_gain = new_replay_gain;
+                    }
+                    Command::ModifyChannelMatrix(new_custom_channel_matrix) => {
+                        custom_channel_matrix = new_custom_channel_matrix;
+
+                        if let Some(source_scheduler) = source_scheduler.as_mut() {
+                            source_scheduler
+                                .set_custom_channel_matrix(custom_channel_matrix.as_ref());
+                        }
+
+                        if let Some((queued_source_scheduler, _)) = queued_source_scheduler.as_mut()
+                        {
+                            queued_source_scheduler
+                                .set_custom_channel_matrix(custom_channel_matrix.as_ref());
+                        }
+
+                        if let Some((fading_source_scheduler, _, _)) =
+                            fading_source_scheduler.as_mut()
+                        {
+                            fading_source_scheduler
+                                .set_custom_channel_matrix(custom_channel_matrix.as_ref());
+                        }
+                    }
+                    Command::ModifyUpmix(new_upmix_is_enabled) => {
+                        upmix_is_enabled = new_upmix_is_enabled;
+
+                        if let Some(device_scheduler) = device_scheduler.as_mut() {
+                            match device_scheduler {
+                                Ok(device_scheduler) => device_scheduler,
+                                Err(device_scheduler) => device_scheduler,
+                            }
+                            .set_upmix(upmix_is_enabled);
+                        }
+                    }
+                    Command::ModifyStereoImage(new_stereo_image) => {
+                        stereo_image = new_stereo_image;
+
+                        if let Some(device_scheduler) = device_scheduler.as_mut() {
+                            match device_scheduler {
+                                Ok(device_scheduler) => device_scheduler,
+                                Err(device_scheduler) => device_scheduler,
+                            }
+                            .set_stereo_image(stereo_image);
+                        }
+                    }
+                    Command::ModifyDither(new_dither_mode) => {
+                        dither_mode = new_dither_mode;
+
+                        if let Some(device_scheduler) = device_scheduler.as_mut() {
+                            match device_scheduler {
+                                Ok(device_scheduler) => device_scheduler,
+                                Err(device_scheduler) => device_scheduler,
+                            }
+                            .set_dither(dither_mode);
+                        }
+                    }
+                    Command::ModifyLimiter(new_limiter_ceiling) => {
+                        limiter_ceiling = new_limiter_ceiling;
+
+                        if let Some(device_scheduler) = device_scheduler.as_mut() {
+                            match device_scheduler {
+                                Ok(device_scheduler) => device_scheduler,
+                                Err(device_scheduler) => device_scheduler,
+                            }
+                            .set_limiter(limiter_ceiling.filter(|_| !dsp_chain.get_bypass()));
+                        }
+                    }
+                    Command::ModifyPlaybackRate(new_playback_rate, new_pitch_is_preserved) => {
+                        pitch_is_preserved = new_pitch_is_preserved;
+
+                        if let Some(source_scheduler) = source_scheduler.as_mut() {
+                            if source_scheduler
+                                .adjust_playback_rate(new_playback_rate, pitch_is_preserved)
+                            {
+                                if let Some(Ok(device_scheduler)) = device_scheduler.as_ref() {
+                                    timeline_anchor += device_scheduler.get_written_frame_count()
+                                        as f64
+                                        / device_scheduler.get_sample_rate() as f64
+                                        * (playback_rate - new_playback_rate);
+                                }
+                            } else {
+                                source_scheduler
+                                    .set_playback_rate(new_playback_rate, pitch_is_preserved);
+
+                                seek_is_necessary_with_second = Some(progress);
+                            }
+                        }
+
+                        playback_rate = new_playback_rate;
+
+                        if let Some((queued_source_scheduler, _)) = queued_source_scheduler.as_mut()
+                        {
+                            queued_source_scheduler
+                                .set_playback_rate(playback_rate, pitch_is_preserved);
+                        }
+                    }
+                    Command::ModifyResampleQuality(new_resample_quality) => {
+                        resample_quality = new_resample_quality;
+
+                        if let Some(source_scheduler) = source_scheduler.as_mut() {
+                            source_scheduler.set_resample_quality(resample_quality);
+
+                            seek_is_necessary_with_second = Some(progress);
+                        }
+
+                        if let Some((queued_source_scheduler, _)) = queued_source_scheduler.as_mut()
+                        {
+                            queued_source_scheduler.set_resample_quality(resample_quality);
+                        }
+                    }
+                    Command::ModifyLoop(loop_range) => {
+                        if let Some(source_scheduler) = source_scheduler.as_mut() {
+                            let seek_is_necessary = source_scheduler.set_custom_loop(loop_range);
+
+                            if let Some((start, end)) = loop_range
+                                && (seek_is_necessary || progress < start || progress >= end)
+                            {
+                                seek_is_necessary_with_second = Some(start);
+                            }
+                        } else {
+                            pending_loop_range = loop_range;
+                        }
+                    }
+                    Command::ModifyTagLoop(new_tag_loop_policy) => {
+                        tag_loop_policy = new_tag_loop_policy;
+
+                        if let Some(source_scheduler) = source_scheduler.as_mut() {
+                            source_scheduler.set_tag_loop(tag_loop_policy);
+                        }
+
+                        if let Some((queued_source_scheduler, _)) = queued_source_scheduler.as_mut()
+                        {
+                            queued_source_scheduler.set_tag_loop(tag_loop_policy);
+                        }
+                    }
+                    Command::ModifyDspChain(arrangement) => {
+                        dsp_chain.arrange(&arrangement);
+                    }
+                    Command::ModifyDspStage(config) => {
+                        if let DspStageConfig::Crossfeed(crossfeed_config) = &config
+                            && let Some(device_scheduler) = device_scheduler.as_ref()
+                        {
+                            let endpoint_id = String::from(
+                                match device_scheduler {
+                                    Ok(device_scheduler) => device_scheduler,
+                                    Err(device_scheduler) => device_scheduler,
+                                }
+                                .get_endpoint_id(),
+                            );
+
+                            match crossfeed_config {
+                                Some(crossfeed_config) => {
+                                    crossfeed_config_map.insert(endpoint_id, *crossfeed_config);
+                                }
+                                None => {
+                                    crossfeed_config_map.remove(&endpoint_id);
+                                }
+                            }
+                        }
+
+                        dsp_chain.configure(config);
+                    }
+                    Command::ModifyDspBypass(bypass_is_enabled) => {
+                        dsp_chain.set_bypass(bypass_is_enabled);
+
+                        if let Some(device_scheduler) = device_scheduler.as_mut() {
+                            match device_scheduler {
+                                Ok(device_scheduler) => device_scheduler,
+                                Err(device_scheduler) => device_scheduler,
+                            }
+                            .set_limiter(limiter_ceiling.filter(|_| !dsp_chain.get_bypass()));
+                        }
+                    }
+                    Command::ModifyAnalysis(new_analysis) => {
+                        (analysis_config, analysis_handler) = new_analysis.unzip();
+
+                        if let Some(device_scheduler) = device_scheduler.as_mut() {
+                            match device_scheduler {
+                                Ok(device_scheduler) => device_scheduler,
+                                Err(device_scheduler) => device_scheduler,
+                            }
+                            .set_analysis(analysis_config);
+                        }
+                    }
+                    Command::CorrectDuration(serial, timeline_length) => {
+                        if let Some(source_scheduler) = source_scheduler.as_mut()
+                            && source_scheduler.correct_duration(serial, timeline_length)
+                        {
+                            active_action_handler.call(
+                                Ok((
+                                    String::from("duration"),
+                                    Some(
+                                        source_scheduler
+                                            .get_duration()
+                                            .map_or(String::from("null"), |duration| {
+                                                format!("{:.3}", duration)
+                                            }),
+                                    ),
+                                )),
+                                ThreadsafeFunctionCallMode::Blocking,
+                            );
+                        }
+
+                        if let Some((queued_source_scheduler, _)) = queued_source_scheduler.as_mut()
+                        {
+                            queued_source_scheduler.correct_duration(serial, timeline_length);
+                        }
+
+                        if let Some((fading_source_scheduler, _, _)) =
+                            fading_source_scheduler.as_mut()
+                        {
+                            fading_source_scheduler.correct_duration(serial, timeline_length);
+                        }
                     }
                     Command::SelectTrack(path, new_identifier) => {
                         identifier = Some(new_identifier);
 
                         source_scheduler_is_update = true;
 
-                        match SourceScheduler::try_from((&path, &mut resampler)) {
-                            Ok(new_source_scheduler) => {
+                        queued_source_scheduler = None;
+
+                        match SourceScheduler::try_from(&path) {
+                            Ok(mut new_source_scheduler) => {
+                                new_source_scheduler
+                                    .set_custom_channel_matrix(custom_channel_matrix.as_ref());
+
+                                new_source_scheduler.set_resample_quality(resample_quality);
+
+                                new_source_scheduler
+                                    .set_playback_rate(playback_rate, pitch_is_preserved);
+
+                                new_source_scheduler.set_tag_loop(tag_loop_policy);
+
+                                if let Some((start, end)) = pending_loop_range.take() {
+                                    new_source_scheduler.set_custom_loop(Some((start, end)));
+
+                                    if start > 0_f64 {
+                                        seek_is_necessary_with_second = Some(start);
+                                    }
+                                }
+
+                                new_source_scheduler
+                                    .request_duration_refinement(&duration_refinement_tx);
+
                                 source_scheduler = Some(new_source_scheduler);
                             }
                             Err(error) => {
@@ -1630,15 +3344,15 @@ fn run_player_loop(
                                 active_action_handler.call(
                                     Ok((
                                         String::from("exception"),
-                                        Some(format!(
-                                            "\"{}::{}\"",
+                                        Some(to_json_string(&format!(
+                                            "{}::{}",
                                             match error {
                                                 SourceException::InvalidFile =>
                                                     "SourceException::InvalidFile",
                                                 _ => unreachable!(),
                                             },
-                                            identifier.as_ref().unwrap_or(&String::from("NIL"))
-                                        )),
+                                            identifier.as_deref().unwrap_or("NIL")
+                                        ))),
                                     )),
                                     ThreadsafeFunctionCallMode::Blocking,
                                 );
@@ -1653,16 +3367,59 @@ fn run_player_loop(
                         pause_is_necessary = true;
 
                         source_scheduler = None;
+
+                        queued_source_scheduler = None;
+                    }
+                    Command::QueueTrack(path, queued_identifier) => {
+                        match SourceScheduler::try_from(&path) {
+                            Ok(mut new_source_scheduler) => {
+                                new_source_scheduler
+                                    .set_custom_channel_matrix(custom_channel_matrix.as_ref());
+
+                                new_source_scheduler.set_resample_quality(resample_quality);
+
+                                new_source_scheduler
+                                    .set_playback_rate(playback_rate, pitch_is_preserved);
+
+                                new_source_scheduler.set_tag_loop(tag_loop_policy);
+
+                                new_source_scheduler
+                                    .request_duration_refinement(&duration_refinement_tx);
+
+                                queued_source_scheduler =
+                                    Some((new_source_scheduler, queued_identifier));
+                            }
+                            Err(error) => {
+                                queued_source_scheduler = None;
+
+                                active_action_handler.call(
+                                    Ok((
+                                        String::from("exception"),
+                                        Some(to_json_string(&format!(
+                                            "{}::{}",
+                                            match error {
+                                                SourceException::InvalidFile =>
+                                                    "SourceException::InvalidFile",
+                                                _ => unreachable!(),
+                                            },
+                                            queued_identifier
+                                        ))),
+                                    )),
+                                    ThreadsafeFunctionCallMode::Blocking,
+                                );
+                            }
+                        }
+                    }
+                    Command::ClearQueuedTrack => {
+                        queued_source_scheduler = None;
                     }
                     Command::Seek(second) => {
-                        seek_is_necessary_with_second = Some(
-                            second.clamp(
-                                0,
-                                source_scheduler
-                                    .as_ref()
-                                    .map_or(0, |source_scheduler| source_scheduler.get_duration()),
-                            ),
-                        );
+                        seek_is_necessary_with_second = Some(second.clamp(
+                            0_f64,
+                            source_scheduler.as_ref().map_or(0_f64, |source_scheduler| {
+                                source_scheduler.get_duration().unwrap_or(f64::INFINITY)
+                            }),
+                        ));
                     }
                     Command::Play => {
                         if identifier.is_some() {
@@ -1677,21 +3434,26 @@ fn run_player_loop(
                     Command::Stop => {
                         pause_is_necessary = true;
 
-                        seek_is_necessary_with_second = Some(0);
+                        seek_is_necessary_with_second = Some(0_f64);
                     }
                     Command::Close => {
                         break 'entry;
                     }
                 }
 
+                if let Some(sample_rate) = device_scheduler_is_update_with_sample_rate {
+                    dsp_chain.set_sample_rate(sample_rate);
+
+                    gain_ramp.set_sample_rate(sample_rate);
+                }
+
                 if let Some(sample_rate) = device_scheduler_is_update_with_sample_rate
                     && let Some(source_scheduler) = source_scheduler.as_mut()
                 {
                     if source_scheduler.determine_additional_seek_necessity() {
-                        match source_scheduler.seek(progress.floor() as u32, Some(progress.fract()))
-                        {
-                            Ok((second, fraction)) => {
-                                timeline_anchor = second as f64 + fraction;
+                        match source_scheduler.seek(progress) {
+                            Ok(second) => {
+                                timeline_anchor = second;
 
                                 timeline_anchor_is_update = true;
                             }
@@ -1709,8 +3471,13 @@ fn run_player_loop(
                         Ok((
                             String::from("track"),
                             Some(format!(
-                                "\"{}\"",
-                                identifier.as_ref().unwrap_or(&String::from("NIL"))
+                                "{{\"identifier\":{},\"gain\":{}}}",
+                                to_json_string(identifier.as_deref().unwrap_or("NIL")),
+                                source_scheduler
+                                    .as_ref()
+                                    .and_then(|source_scheduler| replay_gain
+                                        .get_gain(source_scheduler.get_replay_gain_info()))
+                                    .map_or(String::from("null"), to_json_number)
                             )),
                         )),
                         ThreadsafeFunctionCallMode::Blocking,
@@ -1737,14 +3504,14 @@ fn run_player_loop(
                 }
 
                 if let Some(second) = seek_is_necessary_with_second {
-                    timeline_anchor = second as f64;
+                    timeline_anchor = second;
 
                     timeline_anchor_is_update = true;
 
                     if let Some(source_scheduler) = source_scheduler.as_mut() {
-                        match source_scheduler.seek(second, None) {
-                            Ok((second, fraction)) => {
-                                timeline_anchor = second as f64 + fraction;
+                        match source_scheduler.seek(second) {
+                            Ok(second) => {
+                                timeline_anchor = second;
 
                                 if let Some(Ok(device_scheduler)) = device_scheduler.as_mut() {
                                     source_scheduler
@@ -1766,15 +3533,15 @@ fn run_player_loop(
                     active_action_handler.call(
                         Ok((
                             String::from("exception"),
-                            Some(format!(
-                                "\"{}::{}\"",
+                            Some(to_json_string(&format!(
+                                "{}::{}",
                                 match error {
                                     SourceException::IncorrectFile =>
                                         "SourceException::IncorrectFile",
                                     _ => unreachable!(),
                                 },
-                                identifier.as_ref().unwrap_or(&String::from("NIL"))
-                            )),
+                                identifier.as_deref().unwrap_or("NIL")
+                            ))),
                         )),
                         ThreadsafeFunctionCallMode::Blocking,
                     );
@@ -1784,10 +3551,7 @@ fn run_player_loop(
                     progress = timeline_anchor;
 
                     active_action_handler.call(
-                        Ok((
-                            String::from("progress"),
-                            Some((progress.floor() as u32).to_string()),
-                        )),
+                        Ok((String::from("progress"), Some(format!("{:.3}", progress)))),
                         ThreadsafeFunctionCallMode::Blocking,
                     );
                 }
@@ -1813,35 +3577,285 @@ fn run_player_loop(
 
             let mut source_scheduler_is_incorrect = false;
 
+            let mut queued_source_scheduler_is_expired = false;
+
+            let mut queued_source_scheduler_is_incorrect = false;
+
+            let mut fading_source_scheduler_is_expired = false;
+
+            if is_playing
+                && fading_source_scheduler.is_none()
+                && crossfade.get_duration() > 0_f64
+                && let Some(Ok(device_scheduler)) = device_scheduler.as_ref()
+                && let Some(source_scheduler) = source_scheduler.as_mut()
+                && let Some((mut new_source_scheduler, new_identifier)) = queued_source_scheduler
+                    .take_if(|(queued_source_scheduler, _)| {
+                        source_scheduler.get_remaining_duration()
+                            <= crossfade.get_duration() * playback_rate
+                            && !source_scheduler.determine_same_album(queued_source_scheduler)
+                    })
+            {
+                let sample_rate = device_scheduler.get_sample_rate();
+
+                new_source_scheduler.set_output_sample_rate(sample_rate);
+
+                track_transition_with_boundary = Some((
+                    device_scheduler.get_written_frame_count() as f64 / sample_rate as f64,
+                    new_identifier,
+                ));
+
+                let fade_frame_count = ((source_scheduler.get_remaining_duration() / playback_rate
+                    * sample_rate as f64)
+                    .round() as u64)
+                    .max(1);
+
+                fading_source_scheduler = Some((
+                    mem::replace(source_scheduler, new_source_scheduler),
+                    0,
+                    fade_frame_count,
+                ));
+            }
+
             if is_playing
                 && let Some(Ok(device_scheduler)) = device_scheduler.as_mut()
                 && let Some(source_scheduler) = source_scheduler.as_mut()
             {
                 match device_scheduler.get_expected_frame_count() {
                     Ok(expected_frame_count) => {
-                        if let Ok(source) =
-                            source_scheduler.request_source_stream(expected_frame_count)
+                        if let Ok(source) = source_scheduler
+                            .request_source_stream(expected_frame_count)
+                            .map(|source| {
+                                SourceStream::amplify(
+                                    source,
+                                    replay_gain
+                                        .get_amplitude(source_scheduler.get_replay_gain_info()),
+                                )
+                            })
                         {
-                            if let Ok(has_finished) = device_scheduler.play(source, volume.get()) {
-                                if has_finished {
-                                    progress = source_scheduler.get_duration() as f64;
-                                } else if let Ok(timeline_offset) =
-                                    device_scheduler.get_timeline_offset()
-                                {
-                                    progress = timeline_anchor + timeline_offset;
-                                } else {
-                                    device_scheduler_is_invalid_with_error =
-                                        Some(DeviceException::SilentException);
-                                }
+                            let source = if !matches!(source, Some(SourceStream::Continue(_)))
+                                && let Some((queued_source_scheduler, queued_identifier)) =
+                                    queued_source_scheduler.as_mut()
+                            {
+                                let boundary_frame_count =
+                                    SourceStream::len(source.as_ref()) as u32;
+
+                                queued_source_scheduler
+                                    .set_output_sample_rate(device_scheduler.get_sample_rate());
+
+                                match queued_source_scheduler
+                                    .request_source_stream(
+                                        expected_frame_count - boundary_frame_count,
+                                    )
+                                    .map(|queued_source| {
+                                        SourceStream::amplify(
+                                            queued_source,
+                                            replay_gain.get_amplitude(
+                                                queued_source_scheduler.get_replay_gain_info(),
+                                            ),
+                                        )
+                                    }) {
+                                    Ok(queued_source) => {
+                                        track_transition_with_boundary = Some((
+                                            (device_scheduler.get_written_frame_count()
+                                                + boundary_frame_count as u64)
+                                                as f64
+                                                / device_scheduler.get_sample_rate() as f64,
+                                            queued_identifier.clone(),
+                                        ));
 
-                                active_action_handler.call(
-                                    Ok((
+                                        mem::swap(source_scheduler, queued_source_scheduler);
+
+                                        queued_source_scheduler_is_expired = true;
+
+                                        SourceStream::splice(source, queued_source)
+                                    }
+                                    Err(_) => {
+                                        queued_source_scheduler_is_incorrect = true;
+
+                                        source
+                                    }
+                                }
+                            } else {
+                                source
+                            };
+
+                            let source = if let Some((
+                                fading_source_scheduler,
+                                elapsed_frame_count,
+                                fade_frame_count,
+                            )) = fading_source_scheduler.as_mut()
+                            {
+                                match fading_source_scheduler
+                                    .request_source_stream(expected_frame_count)
+                                    .map(|fading_source| {
+                                        SourceStream::amplify(
+                                            fading_source,
+                                            replay_gain.get_amplitude(
+                                                fading_source_scheduler.get_replay_gain_info(),
+                                            ),
+                                        )
+                                    }) {
+                                    Ok(fading_source) => {
+                                        let fading_source_frame_count =
+                                            SourceStream::len(fading_source.as_ref()) as u64;
+
+                                        let source =
+                                            SourceStream::mix(fading_source, source, |index| {
+                                                crossfade.get_gain(
+                                                    (*elapsed_frame_count + index as u64) as f64
+                                                        / *fade_frame_count as f64,
+                                                )
+                                            });
+
+                                        *elapsed_frame_count += fading_source_frame_count;
+
+                                        if fading_source_frame_count < expected_frame_count as u64
+                                            || *elapsed_frame_count >= *fade_frame_count
+                                        {
+                                            fading_source_scheduler_is_expired = true;
+                                        }
+
+                                        source
+                                    }
+                                    Err(_) => {
+                                        fading_source_scheduler_is_expired = true;
+
+                                        source
+                                    }
+                                }
+                            } else {
+                                source
+                            };
+
+                            let source = SourceStream::process(source, &mut dsp_chain);
+
+                            if !device_scheduler.get_has_started()
+                                && deferred_command_vector.is_empty()
+                            {
+                                gain_ramp.set_gain(0_f32);
+
+                                gain_ramp.ramp_to(volume.get(), ramp_duration.get_fade_in());
+                            }
+
+                            if let Ok(has_finished) = device_scheduler.play(source, &mut gain_ramp)
+                            {
+                                let timeline_offset = device_scheduler.get_timeline_offset();
+
+                                let latency_second = (dsp_chain.get_latency()
+                                    + device_scheduler.get_latency())
+                                    as f64
+                                    / device_scheduler.get_sample_rate() as f64;
+
+                                if let Some((boundary, new_identifier)) =
+                                    track_transition_with_boundary.take_if(|(boundary, _)| {
+                                        has_finished
+                                            || timeline_offset.as_ref().is_ok_and(
+                                                |timeline_offset| {
+                                                    timeline_offset - latency_second >= *boundary
+                                                },
+                                            )
+                                    })
+                                {
+                                    active_action_handler.call(
+                                        Ok((String::from("finish"), None)),
+                                        ThreadsafeFunctionCallMode::Blocking,
+                                    );
+
+                                    active_action_handler.call(
+                                        Ok((
+                                            String::from("track"),
+                                            Some(format!(
+                                                "{{\"identifier\":{},\"gain\":{}}}",
+                                                to_json_string(&new_identifier),
+                                                replay_gain
+                                                    .get_gain(
+                                                        source_scheduler.get_replay_gain_info()
+                                                    )
+                                                    .map_or(String::from("null"), to_json_number)
+                                            )),
+                                        )),
+                                        ThreadsafeFunctionCallMode::Blocking,
+                                    );
+
+                                    active_action_handler.call(
+                                        Ok((
+                                            String::from("duration"),
+                                            Some(
+                                                source_scheduler
+                                                    .get_duration()
+                                                    .map_or(String::from("null"), |duration| {
+                                                        format!("{:.3}", duration)
+                                                    }),
+                                            ),
+                                        )),
+                                        ThreadsafeFunctionCallMode::Blocking,
+                                    );
+
+                                    identifier = Some(new_identifier);
+
+                                    timeline_anchor = -boundary * playback_rate;
+                                }
+
+                                let analysis_frame = timeline_offset
+                                    .as_ref()
+                                    .ok()
+                                    .filter(|_| analysis_handler.is_some())
+                                    .and_then(|timeline_offset| {
+                                        device_scheduler.request_analysis(*timeline_offset)
+                                    });
+
+                                if has_finished {
+                                    progress = source_scheduler.get_duration().unwrap_or(progress);
+                                } else if let Ok(timeline_offset) = timeline_offset {
+                                    progress = source_scheduler.fold_progress(
+                                        timeline_anchor,
+                                        timeline_anchor
+                                            + (timeline_offset - latency_second).max(0_f64)
+                                                * playback_rate,
+                                    );
+                                } else {
+                                    device_scheduler_is_invalid_with_error =
+                                        Some(DeviceException::SilentException);
+                                }
+
+                                active_action_handler.call(
+                                    Ok((
                                         String::from("progress"),
-                                        Some((progress.floor() as u32).to_string()),
+                                        Some(format!("{:.3}", progress)),
                                     )),
                                     ThreadsafeFunctionCallMode::Blocking,
                                 );
 
+                                let gain_reduction = (
+                                    (dsp_chain.take_gain_reduction() * 10_f64).round() / 10_f64,
+                                    (device_scheduler.take_gain_reduction() * 10_f64).round()
+                                        / 10_f64,
+                                );
+
+                                if gain_reduction != reported_gain_reduction {
+                                    reported_gain_reduction = gain_reduction;
+
+                                    active_action_handler.call(
+                                        Ok((
+                                            String::from("reduction"),
+                                            Some(format!(
+                                                "{{\"compressor\":{},\"limiter\":{}}}",
+                                                gain_reduction.0, gain_reduction.1
+                                            )),
+                                        )),
+                                        ThreadsafeFunctionCallMode::Blocking,
+                                    );
+                                }
+
+                                if let Some(analysis_handler) = analysis_handler.as_ref()
+                                    && let Some(analysis_frame) = analysis_frame
+                                {
+                                    analysis_handler.call(
+                                        Ok(analysis_frame.to_json(progress)),
+                                        ThreadsafeFunctionCallMode::NonBlocking,
+                                    );
+                                }
+
                                 if has_finished {
                                     active_action_handler.call(
                                         Ok((String::from("finish"), None)),
@@ -1892,16 +3906,39 @@ fn run_player_loop(
                 );
             }
 
+            if queued_source_scheduler_is_expired {
+                queued_source_scheduler = None;
+            }
+
+            if fading_source_scheduler_is_expired {
+                fading_source_scheduler = None;
+            }
+
+            if queued_source_scheduler_is_incorrect
+                && let Some((_, queued_identifier)) = queued_source_scheduler.take()
+            {
+                active_action_handler.call(
+                    Ok((
+                        String::from("exception"),
+                        Some(to_json_string(&format!(
+                            "SourceException::IncorrectFile::{}",
+                            queued_identifier
+                        ))),
+                    )),
+                    ThreadsafeFunctionCallMode::Blocking,
+                );
+            }
+
             if source_scheduler_is_incorrect {
                 source_scheduler = None;
 
                 active_action_handler.call(
                     Ok((
                         String::from("exception"),
-                        Some(format!(
-                            "\"SourceException::IncorrectFile::{}\"",
-                            identifier.as_ref().unwrap_or(&String::from("NIL"))
-                        )),
+                        Some(to_json_string(&format!(
+                            "SourceException::IncorrectFile::{}",
+                            identifier.as_deref().unwrap_or("NIL")
+                        ))),
                     )),
                     ThreadsafeFunctionCallMode::Blocking,
                 );
@@ -1977,7 +4014,7 @@ impl Player {
     #[napi(
         constructor,
         ts_args_type = "active_action_handler: \
-        (error: null | Error, result: 'initialization' | 'exception' | 'track' | 'progress' | 'state' | 'finish', dataJSON: string) => void"
+        (error: null | Error, result: 'initialization' | 'exception' | 'track' | 'duration' | 'progress' | 'state' | 'volume' | 'reduction' | 'finish', dataJSON: string) => void"
     )]
     pub fn new(active_action_handler: JsFunction) -> Self {
         let (background_task_tx, background_task_handle) = Self::open(active_action_handler);
@@ -2003,6 +4040,7 @@ impl Player {
         #[napi(ts_arg_type = "'default' | 'custom'")] category: String,
         id: String,
         volume: f64,
+        stereo_image: Option<StereoImageOption>,
     ) -> napi::Result<()> {
         if self.is_open() {
             if let Some(background_task_tx) = self.background_task_tx.as_ref() {
@@ -2013,6 +4051,7 @@ impl Player {
                         _ => AudioEndpoint::Default,
                     },
                     Volume::new(volume),
+                    stereo_image.map(StereoImage::from).unwrap_or_default(),
                 ));
             }
 
@@ -2035,6 +4074,431 @@ impl Player {
         }
     }
 
+    #[napi(ts_return_type = "never | void")]
+    pub fn set_volume_db(&self, db: f64) -> napi::Result<()> {
+        if self.is_open() {
+            if let Some(background_task_tx) = self.background_task_tx.as_ref() {
+                let _ = background_task_tx.send(Command::ModifyVolume(Volume::from_db(db)));
+            }
+
+            Ok(())
+        } else {
+            Err(Self::player_not_open_error())
+        }
+    }
+
+    #[napi(ts_return_type = "never | void")]
+    pub fn set_volume_curve(
+        &self,
+        #[napi(ts_arg_type = "'logarithmic' | 'cubic' | 'linear' | 'table'")] curve: String,
+        range_db: Option<f64>,
+        db_table: Option<Vec<f64>>,
+    ) -> napi::Result<()> {
+        let volume_curve = match curve.as_str() {
+            "logarithmic" => VolumeCurve::Logarithmic(
+                range_db
+                    .filter(|range_db| range_db.is_finite())
+                    .map_or(60_f64, |range_db| range_db.clamp(10_f64, 120_f64)),
+            ),
+            "cubic" => VolumeCurve::Cubic,
+            "linear" => VolumeCurve::Linear,
+            "table" => VolumeCurve::Table(
+                db_table
+                    .filter(|db_table| {
+                        db_table.len() >= 2 && db_table.iter().all(|db| db.is_finite())
+                    })
+                    .ok_or_else(|| {
+                        Error::new(
+                            Status::InvalidArg,
+                            String::from(
+                                "The volume table needs at least two finite decibel values",
+                            ),
+                        )
+                    })?
+                    .into_iter()
+                    .map(|db| db.min(0_f64))
+                    .collect(),
+            ),
+            _ => VolumeCurve::default(),
+        };
+
+        if self.is_open() {
+            if let Some(background_task_tx) = self.background_task_tx.as_ref() {
+                let _ = background_task_tx.send(Command::ModifyVolumeCurve(volume_curve));
+            }
+
+            Ok(())
+        } else {
+            Err(Self::player_not_open_error())
+        }
+    }
+
+    #[napi(ts_return_type = "never | void")]
+    pub fn set_ramp_duration(
+        &self,
+        fade_in_ms: f64,
+        fade_out_ms: f64,
+        volume_ms: Option<f64>,
+    ) -> napi::Result<()> {
+        if self.is_open() {
+            if let Some(background_task_tx) = self.background_task_tx.as_ref() {
+                let _ = background_task_tx.send(Command::ModifyRampDuration(RampDuration::new(
+                    fade_in_ms / 1000_f64,
+                    fade_out_ms / 1000_f64,
+                    volume_ms.map_or(RampDuration::default().get_volume(), |volume_ms| {
+                        volume_ms / 1000_f64
+                    }),
+                )));
+            }
+
+            Ok(())
+        } else {
+            Err(Self::player_not_open_error())
+        }
+    }
+
+    #[napi(ts_return_type = "never | void")]
+    pub fn set_crossfade(
+        &self,
+        second: f64,
+        #[napi(ts_arg_type = "'linear' | 'equal-power' | 's-curve'")] curve: String,
+    ) -> napi::Result<()> {
+        if self.is_open() {
+            if let Some(background_task_tx) = self.background_task_tx.as_ref() {
+                let _ = background_task_tx.send(Command::ModifyCrossfade(Crossfade::new(
+                    second,
+                    match curve.as_str() {
+                        "linear" => CrossfadeCurve::Linear,
+                        "equal-power" => CrossfadeCurve::EqualPower,
+                        "s-curve" => CrossfadeCurve::SCurve,
+                        _ => CrossfadeCurve::EqualPower,
+                    },
+                )));
+            }
+
+            Ok(())
+        } else {
+            Err(Self::player_not_open_error())
+        }
+    }
+
+    #[napi(ts_return_type = "never | void")]
+    pub fn set_replay_gain_mode(
+        &self,
+        #[napi(ts_arg_type = "'off' | 'track' | 'album'")] mode: String,
+        preamp_db: f64,
+        prevent_clipping: bool,
+    ) -> napi::Result<()> {
+        if self.is_open() {
+            if let Some(background_task_tx) = self.background_task_tx.as_ref() {
+                let _ = background_task_tx.send(Command::ModifyReplayGain(ReplayGain::new(
+                    match mode.as_str() {
+                        "off" => ReplayGainMode::Off,
+                        "track" => ReplayGainMode::Track,
+                        "album" => ReplayGainMode::Album,
+                        _ => ReplayGainMode::Off,
+                    },
+                    preamp_db,
+                    prevent_clipping,
+                )));
+            }
+
+            Ok(())
+        } else {
+            Err(Self::player_not_open_error())
+        }
+    }
+
+    #[napi(ts_return_type = "never | void")]
+    pub fn set_stereo_image(&self, stereo_image: StereoImageOption) -> napi::Result<()> {
+        if self.is_open() {
+            if let Some(background_task_tx) = self.background_task_tx.as_ref() {
+                let _ = background_task_tx
+                    .send(Command::ModifyStereoImage(StereoImage::from(stereo_image)));
+            }
+
+            Ok(())
+        } else {
+            Err(Self::player_not_open_error())
+        }
+    }
+
+    #[napi(ts_return_type = "never | void")]
+    pub fn set_dither(
+        &self,
+        #[napi(ts_arg_type = "'none' | 'tpdf' | 'shaped'")] mode: String,
+    ) -> napi::Result<()> {
+        if self.is_open() {
+            if let Some(background_task_tx) = self.background_task_tx.as_ref() {
+                let _ = background_task_tx.send(Command::ModifyDither(match mode.as_str() {
+                    "none" => DitherMode::None,
+                    "tpdf" => DitherMode::Triangular,
+                    "shaped" => DitherMode::Shaped,
+                    _ => DitherMode::default(),
+                }));
+            }
+
+            Ok(())
+        } else {
+            Err(Self::player_not_open_error())
+        }
+    }
+
+    #[napi(ts_return_type = "never | void")]
+    pub fn set_limiter(&self, enabled: bool, ceiling_db: Option<f64>) -> napi::Result<()> {
+        if self.is_open() {
+            if let Some(background_task_tx) = self.background_task_tx.as_ref() {
+                let _ = background_task_tx.send(Command::ModifyLimiter(enabled.then(|| {
+                    ceiling_db
+                        .filter(|ceiling_db| ceiling_db.is_finite())
+                        .map_or(-1_f64, |ceiling_db| ceiling_db.clamp(-12_f64, 0_f64))
+                })));
+            }
+
+            Ok(())
+        } else {
+            Err(Self::player_not_open_error())
+        }
+    }
+
+    #[napi(ts_return_type = "never | void")]
+    pub fn set_upmix(&self, enabled: bool) -> napi::Result<()> {
+        if self.is_open() {
+            if let Some(background_task_tx) = self.background_task_tx.as_ref() {
+                let _ = background_task_tx.send(Command::ModifyUpmix(enabled));
+            }
+
+            Ok(())
+        } else {
+            Err(Self::player_not_open_error())
+        }
+    }
+
+    #[napi(ts_return_type = "never | void")]
+    pub fn set_downmix_matrix(
+        &self,
+        #[napi(ts_arg_type = "null | number[][]")] matrix: Option<Vec<Vec<f64>>>,
+    ) -> napi::Result<()> {
+        if self.is_open() {
+            if let Some(background_task_tx) = self.background_task_tx.as_ref() {
+                let _ = background_task_tx.send(Command::ModifyChannelMatrix(matrix));
+            }
+
+            Ok(())
+        } else {
+            Err(Self::player_not_open_error())
+        }
+    }
+
+    #[napi(ts_return_type = "never | void")]
+    pub fn set_playback_rate(
+        &self,
+        rate: f64,
+        #[napi(ts_arg_type = "'varispeed' | 'preserve-pitch'")] mode: Option<String>,
+    ) -> napi::Result<()> {
+        if self.is_open() {
+            if let Some(background_task_tx) = self.background_task_tx.as_ref() {
+                let _ = background_task_tx.send(Command::ModifyPlaybackRate(
+                    if rate.is_finite() {
+                        rate.clamp(*PLAYBACK_RATE_RANGE.start(), *PLAYBACK_RATE_RANGE.end())
+                    } else {
+                        1_f64
+                    },
+                    !matches!(mode.as_deref(), Some("varispeed")),
+                ));
+            }
+
+            Ok(())
+        } else {
+            Err(Self::player_not_open_error())
+        }
+    }
+
+    #[napi(ts_return_type = "never | void")]
+    pub fn set_resample_quality(
+        &self,
+        #[napi(ts_arg_type = "'fast' | 'balanced' | 'best'")] quality: String,
+    ) -> napi::Result<()> {
+        if self.is_open() {
+            if let Some(background_task_tx) = self.background_task_tx.as_ref() {
+                let _ = background_task_tx.send(Command::ModifyResampleQuality(
+                    match quality.as_str() {
+                        "fast" => ResampleQuality::Fast,
+                        "balanced" => ResampleQuality::Balanced,
+                        "best" => ResampleQuality::Best,
+                        _ => ResampleQuality::default(),
+                    },
+                ));
+            }
+
+            Ok(())
+        } else {
+            Err(Self::player_not_open_error())
+        }
+    }
+
+    #[napi(ts_return_type = "never | void")]
+    pub fn set_loop(&self, start_ms: Option<f64>, end_ms: Option<f64>) -> napi::Result<()> {
+        if self.is_open() {
+            if let Some(background_task_tx) = self.background_task_tx.as_ref() {
+                let _ = background_task_tx.send(Command::ModifyLoop(
+                    start_ms
+                        .zip(end_ms)
+                        .filter(|(start_ms, end_ms)| {
+                            start_ms.is_finite() && end_ms.is_finite() && end_ms > start_ms
+                        })
+                        .map(|(start_ms, end_ms)| {
+                            (start_ms.max(0_f64) / 1000_f64, end_ms / 1000_f64)
+                        }),
+                ));
+            }
+
+            Ok(())
+        } else {
+            Err(Self::player_not_open_error())
+        }
+    }
+
+    #[napi(ts_return_type = "never | void")]
+    pub fn set_tag_loop(
+        &self,
+        enabled: bool,
+        count: Option<u32>,
+        fade_second: Option<f64>,
+    ) -> napi::Result<()> {
+        if self.is_open() {
+            if let Some(background_task_tx) = self.background_task_tx.as_ref() {
+                let _ = background_task_tx.send(Command::ModifyTagLoop(
+                    enabled.then(|| LoopPolicy::new(count, fade_second.unwrap_or(0_f64))),
+                ));
+            }
+
+            Ok(())
+        } else {
+            Err(Self::player_not_open_error())
+        }
+    }
+
+    #[napi(ts_return_type = "never | void")]
+    pub fn set_dsp_chain(&self, stage_list: Vec<DspStageArrangement>) -> napi::Result<()> {
+        if self.is_open() {
+            if let Some(background_task_tx) = self.background_task_tx.as_ref() {
+                let _ = background_task_tx.send(Command::ModifyDspChain(
+                    stage_list
+                        .into_iter()
+                        .map(|stage| (stage.id, stage.enabled))
+                        .collect(),
+                ));
+            }
+
+            Ok(())
+        } else {
+            Err(Self::player_not_open_error())
+        }
+    }
+
+    #[napi(ts_return_type = "never | void")]
+    pub fn configure_dsp_stage(
+        &self,
+        env: Env,
+        #[napi(ts_arg_type = "'equalizer' | 'crossfeed' | 'compressor'")] id: String,
+        #[napi(ts_arg_type = "null | EqualizerPreset | CrossfeedPreset | CompressorPreset")]
+        config: Option<JsUnknown>,
+    ) -> napi::Result<()> {
+        if self.is_open() {
+            let config = match id.as_str() {
+                "equalizer" => DspStageConfig::Equalizer(
+                    config
+                        .map(|config| unsafe {
+                            EqualizerPreset::from_napi_value(env.raw(), config.raw())
+                        })
+                        .transpose()?
+                        .map(EqualizerConfig::from),
+                ),
+                "crossfeed" => DspStageConfig::Crossfeed(
+                    config
+                        .map(|config| unsafe {
+                            CrossfeedPreset::from_napi_value(env.raw(), config.raw())
+                        })
+                        .transpose()?
+                        .map(CrossfeedConfig::from),
+                ),
+                "compressor" => DspStageConfig::Compressor(
+                    config
+                        .map(|config| unsafe {
+                            CompressorPreset::from_napi_value(env.raw(), config.raw())
+                        })
+                        .transpose()?
+                        .map(CompressorConfig::from),
+                ),
+                _ => {
+                    return Err(Error::new(
+                        Status::InvalidArg,
+                        format!("The DSP stage {} does not exist", id),
+                    ));
+                }
+            };
+
+            if let Some(background_task_tx) = self.background_task_tx.as_ref() {
+                let _ = background_task_tx.send(Command::ModifyDspStage(config));
+            }
+
+            Ok(())
+        } else {
+            Err(Self::player_not_open_error())
+        }
+    }
+
+    #[napi(ts_return_type = "never | void")]
+    pub fn set_dsp_bypass(&self, enabled: bool) -> napi::Result<()> {
+        if self.is_open() {
+            if let Some(background_task_tx) = self.background_task_tx.as_ref() {
+                let _ = background_task_tx.send(Command::ModifyDspBypass(enabled));
+            }
+
+            Ok(())
+        } else {
+            Err(Self::player_not_open_error())
+        }
+    }
+
+    #[napi(ts_return_type = "never | void")]
+    pub fn set_analysis_handler(
+        &self,
+        #[napi(ts_arg_type = "null | ((error: null | Error, dataJSON: string) => void)")]
+        analysis_handler: Option<JsFunction>,
+        band_count: Option<u32>,
+        refresh_rate: Option<f64>,
+    ) -> napi::Result<()> {
+        if self.is_open() {
+            let analysis = analysis_handler
+                .map(|analysis_handler| {
+                    analysis_handler
+                        .create_threadsafe_function(2, |ctx| {
+                            Ok(vec![ctx.env.create_string_from_std(ctx.value)?])
+                        })
+                        .map(|analysis_handler| {
+                            (
+                                AnalysisConfig::new(
+                                    band_count.unwrap_or(32),
+                                    refresh_rate.unwrap_or(30_f64),
+                                ),
+                                analysis_handler,
+                            )
+                        })
+                })
+                .transpose()?;
+
+            if let Some(background_task_tx) = self.background_task_tx.as_ref() {
+                let _ = background_task_tx.send(Command::ModifyAnalysis(analysis));
+            }
+
+            Ok(())
+        } else {
+            Err(Self::player_not_open_error())
+        }
+    }
+
     #[napi(ts_return_type = "never | void")]
     pub fn select_file(&self, path: String, identifier: String) -> napi::Result<()> {
         if self.is_open() {
@@ -2051,11 +4515,31 @@ impl Player {
         }
     }
 
+    #[napi(ts_return_type = "never | void")]
+    pub fn queue_next(&self, path: String, identifier: String) -> napi::Result<()> {
+        if self.is_open() {
+            if let Some(background_task_tx) = self.background_task_tx.as_ref() {
+                let _ = background_task_tx.send(match path.as_str() {
+                    "" => Command::ClearQueuedTrack,
+                    _ => Command::QueueTrack(path, identifier),
+                });
+            }
+
+            Ok(())
+        } else {
+            Err(Self::player_not_open_error())
+        }
+    }
+
     #[napi(ts_return_type = "never | void")]
     pub fn seek_to(&self, second: f64) -> napi::Result<()> {
         if self.is_open() {
             if let Some(background_task_tx) = self.background_task_tx.as_ref() {
-                let _ = background_task_tx.send(Command::Seek(second.floor() as u32));
+                let _ = background_task_tx.send(Command::Seek(if second.is_finite() {
+                    second.max(0_f64)
+                } else {
+                    0_f64
+                }));
             }
 
             Ok(())
@@ -0,0 +1,62 @@
+pub struct GainRamp {
+    sample_rate: u32,
+    gain: f32,
+    target_gain: f32,
+    step: f32,
+}
+
+impl GainRamp {
+    pub fn new(gain: f32, sample_rate: u32) -> Self {
+        Self {
+            sample_rate,
+            gain,
+            target_gain: gain,
+            step: 0_f32,
+        }
+    }
+
+    pub fn set_sample_rate(&mut self, sample_rate: u32) {
+        self.sample_rate = sample_rate;
+    }
+
+    pub fn get_is_silent(&self) -> bool {
+        self.gain == 0_f32 && self.target_gain == 0_f32
+    }
+
+    pub fn set_gain(&mut self, gain: f32) {
+        self.gain = gain;
+
+        self.target_gain = gain;
+
+        self.step = 0_f32;
+    }
+
+    pub fn ramp_to(&mut self, target_gain: f32, duration: f64) {
+        let frame_count = (duration * self.sample_rate as f64).round();
+
+        if frame_count < 1_f64 {
+            self.set_gain(target_gain);
+        } else {
+            self.target_gain = target_gain;
+
+            self.step = (target_gain - self.gain) / frame_count as f32;
+        }
+    }
+
+    pub fn request_gain(&mut self) -> f32 {
+        let gain = self.gain;
+
+        if self.gain != self.target_gain {
+            self.gain += self.step;
+
+            if (self.step > 0_f32 && self.gain >= self.target_gain)
+                || (self.step < 0_f32 && self.gain <= self.target_gain)
+                || self.step == 0_f32
+            {
+                self.gain = self.target_gain;
+            }
+        }
+
+        gain
+    }
+}
@@ -0,0 +1,273 @@
+use rubato::{
+    FastFixedIn, FftFixedIn, PolynomialDegree, ResampleResult, SincFixedIn,
+    SincInterpolationParameters, SincInterpolationType, VecResampler, WindowFunction,
+    calculate_cutoff,
+};
+
+const CHUNK_SIZE: usize = 1024;
+
+const MAX_RATIO_RELATIVE: f64 = 8_f64;
+
+#[derive(Clone, Copy, Default, PartialEq)]
+pub enum ResampleQuality {
+    Fast,
+    #[default]
+    Balanced,
+    Best,
+}
+
+pub struct SourceResampler {
+    quality: ResampleQuality,
+    sample_rate: u32,
+    output_sample_rate: u32,
+    media_rate_factor: f64,
+    channel_count: usize,
+    resampler: Option<Box<dyn VecResampler<f32>>>,
+}
+
+impl SourceResampler {
+    pub fn new(
+        quality: ResampleQuality,
+        sample_rate: u32,
+        output_sample_rate: u32,
+        media_rate_factor: f64,
+        channel_count: usize,
+    ) -> Option<Self> {
+        let mut source_resampler = Self {
+            quality,
+            sample_rate,
+            output_sample_rate,
+            media_rate_factor,
+            channel_count,
+            resampler: None,
+        };
+
+        if sample_rate == output_sample_rate && media_rate_factor == 1_f64 {
+            return Some(source_resampler);
+        }
+
+        let ratio = output_sample_rate as f64 / sample_rate as f64 / media_rate_factor;
+
+        match quality {
+            ResampleQuality::Fast => FastFixedIn::<f32>::new(
+                ratio,
+                MAX_RATIO_RELATIVE,
+                PolynomialDegree::Cubic,
+                CHUNK_SIZE,
+                channel_count,
+            )
+            .map(|resampler| Box::new(resampler) as Box<dyn VecResampler<f32>>)
+            .ok(),
+            ResampleQuality::Balanced => SincFixedIn::<f32>::new(
+                ratio,
+                MAX_RATIO_RELATIVE,
+                SincInterpolationParameters {
+                    sinc_len: 256,
+                    f_cutoff: 0.95,
+                    oversampling_factor: 256,
+                    interpolation: SincInterpolationType::Linear,
+                    window: WindowFunction::BlackmanHarris2,
+                },
+                CHUNK_SIZE,
+                channel_count,
+            )
+            .map(|resampler| Box::new(resampler) as Box<dyn VecResampler<f32>>)
+            .ok(),
+            ResampleQuality::Best if media_rate_factor == 1_f64 => FftFixedIn::<f32>::new(
+                sample_rate as usize,
+                output_sample_rate as usize,
+                CHUNK_SIZE,
+                2,
+                channel_count,
+            )
+            .map(|resampler| Box::new(resampler) as Box<dyn VecResampler<f32>>)
+            .ok(),
+            ResampleQuality::Best => SincFixedIn::<f32>::new(
+                ratio,
+                MAX_RATIO_RELATIVE,
+                SincInterpolationParameters {
+                    sinc_len: 512,
+                    f_cutoff: calculate_cutoff(512, WindowFunction::BlackmanHarris2),
+                    oversampling_factor: 256,
+                    interpolation: SincInterpolationType::Cubic,
+                    window: WindowFunction::BlackmanHarris2,
+                },
+                CHUNK_SIZE,
+                channel_count,
+            )
+            .map(|resampler| Box::new(resampler) as Box<dyn VecResampler<f32>>)
+            .ok(),
+        }
+        .map(|resampler| {
+            source_resampler.resampler = Some(resampler);
+
+            source_resampler
+        })
+    }
+
+    pub fn set_media_rate_factor(&mut self, media_rate_factor: f64) -> bool {
+        let ratio = self.output_sample_rate as f64 / self.sample_rate as f64 / media_rate_factor;
+
+        let is_adjusted = self
+            .resampler
+            .as_mut()
+            .is_some_and(|resampler| resampler.set_resample_ratio(ratio, true).is_ok());
+
+        if is_adjusted {
+            self.media_rate_factor = media_rate_factor;
+        }
+
+        is_adjusted
+    }
+
+    pub fn get_delay(&self) -> usize {
+        self.resampler
+            .as_ref()
+            .map_or(0, |resampler| resampler.output_delay())
+    }
+
+    pub fn get_input_frame_count(&self) -> usize {
+        self.resampler
+            .as_ref()
+            .map_or(CHUNK_SIZE, |resampler| resampler.input_frames_next())
+    }
+
+    pub fn process(&mut self, wave_in: Vec<Vec<f32>>) -> ResampleResult<Vec<Vec<f32>>> {
+        match self.resampler.as_mut() {
+            Some(resampler) => resampler.process(&wave_in, None),
+            None => Ok(wave_in),
+        }
+    }
+
+    pub fn process_partial(
+        &mut self,
+        wave_in: Option<Vec<Vec<f32>>>,
+    ) -> ResampleResult<Vec<Vec<f32>>> {
+        match self.resampler.as_mut() {
+            Some(resampler) => resampler.process_partial(wave_in.as_deref(), None),
+            None => Ok(wave_in.unwrap_or_default()),
+        }
+    }
+
+    pub fn reset(&mut self) {
+        if self.resampler.is_some()
+            && let Some(source_resampler) = Self::new(
+                self.quality,
+                self.sample_rate,
+                self.output_sample_rate,
+                self.media_rate_factor,
+                self.channel_count,
+            )
+        {
+            *self = source_resampler;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const QUALITY_LIST: [ResampleQuality; 3] = [
+        ResampleQuality::Fast,
+        ResampleQuality::Balanced,
+        ResampleQuality::Best,
+    ];
+
+    fn request_output_frame_count(
+        source_resampler: &mut SourceResampler,
+        chunk_count: usize,
+    ) -> usize {
+        (0..chunk_count)
+            .map(|_| {
+                let input_frame_count = source_resampler.get_input_frame_count();
+
+                source_resampler
+                    .process(vec![vec![0.25_f32; input_frame_count]; 2])
+                    .unwrap()[0]
+                    .len()
+            })
+            .sum()
+    }
+
+    #[test]
+    fn matching_rates_pass_through() {
+        QUALITY_LIST.iter().for_each(|quality| {
+            let mut source_resampler =
+                SourceResampler::new(*quality, 44_100, 44_100, 1_f64, 2).unwrap();
+
+            let wave_in = vec![
+                (0..CHUNK_SIZE)
+                    .map(|index| index as f32)
+                    .collect::<Vec<f32>>(),
+                (0..CHUNK_SIZE)
+                    .map(|index| -(index as f32))
+                    .collect::<Vec<f32>>(),
+            ];
+
+            assert!(source_resampler.resampler.is_none());
+
+            assert_eq!(source_resampler.get_delay(), 0);
+
+            assert_eq!(source_resampler.get_input_frame_count(), CHUNK_SIZE);
+
+            assert_eq!(source_resampler.process(wave_in.clone()).unwrap(), wave_in);
+
+            assert!(source_resampler.process_partial(None).unwrap().is_empty());
+
+            assert!(!source_resampler.set_media_rate_factor(1.25_f64));
+        });
+    }
+
+    #[test]
+    fn converts_between_rates() {
+        QUALITY_LIST.iter().for_each(|quality| {
+            let mut source_resampler =
+                SourceResampler::new(*quality, 44_100, 48_000, 1_f64, 2).unwrap();
+
+            let input_frame_count = source_resampler.get_input_frame_count() * 64;
+
+            let output_frame_count = request_output_frame_count(&mut source_resampler, 64) as f64;
+
+            assert!(source_resampler.get_delay() > 0);
+
+            assert!(
+                (output_frame_count - input_frame_count as f64 * 48_000_f64 / 44_100_f64).abs()
+                    <= CHUNK_SIZE as f64 * 2_f64
+            );
+        });
+    }
+
+    #[test]
+    fn media_rate_factor_scales_output() {
+        let mut source_resampler =
+            SourceResampler::new(ResampleQuality::Balanced, 48_000, 48_000, 1.25_f64, 2).unwrap();
+
+        let output_frame_count = request_output_frame_count(&mut source_resampler, 64) as f64;
+
+        assert!(
+            (output_frame_count - 64_f64 * CHUNK_SIZE as f64 / 1.25_f64).abs()
+                <= CHUNK_SIZE as f64 * 2_f64
+        );
+
+        assert!(source_resampler.set_media_rate_factor(0.8_f64));
+
+        assert_eq!(source_resampler.media_rate_factor, 0.8_f64);
+
+        source_resampler.reset();
+
+        assert_eq!(source_resampler.media_rate_factor, 0.8_f64);
+
+        assert!(source_resampler.resampler.is_some());
+    }
+
+    #[test]
+    fn fixed_ratio_fft_rejects_rate_adjustment() {
+        let mut source_resampler =
+            SourceResampler::new(ResampleQuality::Best, 44_100, 48_000, 1_f64, 2).unwrap();
+
+        assert!(!source_resampler.set_media_rate_factor(1.25_f64));
+
+        assert_eq!(source_resampler.media_rate_factor, 1_f64);
+    }
+}
@@ -0,0 +1,277 @@
+use std::{collections::VecDeque, f32::consts::PI};
+
+const SEGMENT_DURATION: f64 = 0.03_f64;
+
+const TOLERANCE_DURATION: f64 = 0.01_f64;
+
+const SEARCH_STEP: usize = 2;
+
+const ENERGY_FLOOR: f32 = 1e-9_f32;
+
+pub struct TimeStretcher {
+    rate: f64,
+    segment_length: usize,
+    synthesis_hop: usize,
+    tolerance: usize,
+    window: Vec<f32>,
+    input_buffer: VecDeque<[f32; 2]>,
+    input_offset: u64,
+    analysis_position: f64,
+    previous_position: Option<u64>,
+    output_accumulator: Vec<[f32; 2]>,
+}
+
+impl TimeStretcher {
+    pub fn new(rate: f64, sample_rate: u32) -> Self {
+        let segment_length =
+            ((sample_rate as f64 * SEGMENT_DURATION).round() as usize / 2 * 2).max(64);
+
+        Self {
+            rate,
+            segment_length,
+            synthesis_hop: segment_length / 2,
+            tolerance: (sample_rate as f64 * TOLERANCE_DURATION).round() as usize,
+            window: (0..segment_length)
+                .map(|index| {
+                    0.5_f32 - 0.5_f32 * (2_f32 * PI * index as f32 / segment_length as f32).cos()
+                })
+                .collect(),
+            input_buffer: VecDeque::new(),
+            input_offset: 0,
+            analysis_position: 0_f64,
+            previous_position: None,
+            output_accumulator: vec![[0_f32; 2]; segment_length],
+        }
+    }
+
+    pub fn set_rate(&mut self, rate: f64) {
+        self.rate = rate;
+    }
+
+    fn get_input_frame(&self, position: u64) -> [f32; 2] {
+        position
+            .checked_sub(self.input_offset)
+            .and_then(|index| self.input_buffer.get(index as usize))
+            .copied()
+            .unwrap_or([0_f32; 2])
+    }
+
+    fn find_best_position(&self, nominal_position: u64) -> u64 {
+        let Some(previous_position) = self.previous_position else {
+            return nominal_position;
+        };
+
+        let natural_position = previous_position + self.synthesis_hop as u64;
+
+        let search_start = nominal_position
+            .saturating_sub(self.tolerance as u64)
+            .max(self.input_offset);
+
+        let search_end = nominal_position + self.tolerance as u64;
+
+        (search_start..=search_end)
+            .step_by(SEARCH_STEP)
+            .map(|candidate_position| {
+                let (correlation, natural_energy, candidate_energy) = (0..self.synthesis_hop)
+                    .step_by(SEARCH_STEP)
+                    .map(|index| {
+                        let [natural_left, natural_right] =
+                            self.get_input_frame(natural_position + index as u64);
+
+                        let [candidate_left, candidate_right] =
+                            self.get_input_frame(candidate_position + index as u64);
+
+                        let natural = natural_left + natural_right;
+
+                        let candidate = candidate_left + candidate_right;
+
+                        (
+                            natural * candidate,
+                            natural * natural,
+                            candidate * candidate,
+                        )
+                    })
+                    .fold(
+                        (0_f32, 0_f32, 0_f32),
+                        |(correlation, natural_energy, candidate_energy),
+                         (product, natural_square, candidate_square)| {
+                            (
+                                correlation + product,
+                                natural_energy + natural_square,
+                                candidate_energy + candidate_square,
+                            )
+                        },
+                    );
+
+                (
+                    candidate_position,
+                    correlation / (natural_energy * candidate_energy).sqrt().max(ENERGY_FLOOR),
+                )
+            })
+            .fold((nominal_position, f32::NEG_INFINITY), |best, candidate| {
+                if candidate.1 > best.1 {
+                    candidate
+                } else {
+                    best
+                }
+            })
+            .0
+    }
+
+    fn synthesize(&mut self, target: &mut VecDeque<[f32; 2]>) {
+        let position = self.find_best_position(self.analysis_position.round() as u64);
+
+        (0..self.segment_length).for_each(|index| {
+            let frame = self.get_input_frame(position + index as u64);
+
+            let weight = self.window[index];
+
+            self.output_accumulator[index][0] += frame[0] * weight;
+
+            self.output_accumulator[index][1] += frame[1] * weight;
+        });
+
+        target.extend(self.output_accumulator.drain(..self.synthesis_hop));
+
+        self.output_accumulator
+            .extend((0..self.synthesis_hop).map(|_| [0_f32; 2]));
+
+        self.previous_position = Some(position);
+
+        self.analysis_position += self.synthesis_hop as f64 * self.rate;
+
+        let obsolete_frame_count = (self.analysis_position as u64)
+            .saturating_sub(self.tolerance as u64)
+            .min(position + self.synthesis_hop as u64)
+            .saturating_sub(self.input_offset)
+            .min(self.input_buffer.len() as u64);
+
+        self.input_buffer.drain(..obsolete_frame_count as usize);
+
+        self.input_offset += obsolete_frame_count;
+    }
+
+    fn get_required_end(&self) -> u64 {
+        (self.analysis_position.round() as u64 + self.tolerance as u64).max(
+            self.previous_position.map_or(0, |previous_position| {
+                previous_position + self.synthesis_hop as u64
+            }),
+        ) + self.segment_length as u64
+    }
+
+    pub fn process(&mut self, source: &[[f32; 2]], target: &mut VecDeque<[f32; 2]>) {
+        self.input_buffer.extend(source);
+
+        while self.input_offset + self.input_buffer.len() as u64 >= self.get_required_end() {
+            self.synthesize(target);
+        }
+    }
+
+    pub fn flush(&mut self, target: &mut VecDeque<[f32; 2]>) {
+        if self.input_buffer.is_empty() && self.previous_position.is_none() {
+            return;
+        }
+
+        let input_end = self.input_offset + self.input_buffer.len() as u64;
+
+        while (self.analysis_position as u64) < input_end {
+            self.synthesize(target);
+        }
+
+        target.extend(self.output_accumulator.drain(..self.synthesis_hop));
+
+        self.reset();
+    }
+
+    pub fn reset(&mut self) {
+        self.input_buffer.clear();
+
+        self.input_offset = 0;
+
+        self.analysis_position = 0_f64;
+
+        self.previous_position = None;
+
+        self.output_accumulator = vec![[0_f32; 2]; self.segment_length];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: u32 = 48_000;
+
+    fn stretch(rate: f64, frequency: f32, frame_count: usize) -> Vec<[f32; 2]> {
+        let source = (0..frame_count)
+            .map(|index| {
+                let sample = (2_f32 * PI * frequency * index as f32 / SAMPLE_RATE as f32).sin();
+
+                [sample, sample]
+            })
+            .collect::<Vec<[f32; 2]>>();
+
+        let mut time_stretcher = TimeStretcher::new(rate, SAMPLE_RATE);
+
+        let mut target = VecDeque::new();
+
+        source
+            .chunks(1_024)
+            .for_each(|chunk| time_stretcher.process(chunk, &mut target));
+
+        time_stretcher.flush(&mut target);
+
+        target.into_iter().collect()
+    }
+
+    #[test]
+    fn output_length_follows_rate() {
+        [0.5_f64, 1_f64, 1.25_f64, 2_f64].iter().for_each(|rate| {
+            let output_length = stretch(*rate, 440_f32, 24_000).len() as f64;
+
+            assert!((output_length - 24_000_f64 / rate).abs() <= 1_440_f64);
+        });
+    }
+
+    #[test]
+    fn stretching_preserves_pitch_and_level() {
+        [0.75_f64, 1_f64, 1.5_f64].iter().for_each(|rate| {
+            let output = stretch(*rate, 440_f32, 24_000);
+
+            let steady = &output[2_880..output.len() - 2_880];
+
+            let crossing_count = steady
+                .windows(2)
+                .filter(|frame_pair| (frame_pair[0][0] < 0_f32) != (frame_pair[1][0] < 0_f32))
+                .count() as f64;
+
+            let frequency = crossing_count / 2_f64 * SAMPLE_RATE as f64 / steady.len() as f64;
+
+            let rms = (steady
+                .iter()
+                .map(|frame| (frame[0] as f64).powi(2))
+                .sum::<f64>()
+                / steady.len() as f64)
+                .sqrt();
+
+            assert!((frequency - 440_f64).abs() < 5_f64);
+
+            assert!((rms - 0.5_f64.sqrt()).abs() < 0.05_f64);
+        });
+    }
+
+    #[test]
+    fn reset_discards_buffered_input() {
+        let mut time_stretcher = TimeStretcher::new(1.5_f64, SAMPLE_RATE);
+
+        let mut target = VecDeque::new();
+
+        time_stretcher.process(&[[1_f32, 1_f32]; 256], &mut target);
+
+        time_stretcher.reset();
+
+        time_stretcher.flush(&mut target);
+
+        assert!(target.is_empty());
+    }
+}
@@ -0,0 +1,550 @@
+use crate::decode::{MediaSource, SourceSignature};
+use napi::{JsObject, bindgen_prelude::*};
+use std::{fs, path::Path};
+use symphonia::core::{
+    audio::SampleBuffer,
+    units::{Time, TimeBase},
+};
+
+const WAVEFORM_CACHE_MAGIC: &[u8; 4] = b"RWF1";
+
+const WAVEFORM_CACHE_HEADER_LENGTH: usize = 24;
+
+const MAX_BUCKET_COUNT: u32 = 65_536;
+
+const BLOCK_COUNT_FACTOR: u64 = 4;
+
+#[derive(Clone, Copy)]
+struct BucketAccumulator {
+    minimum: f32,
+    maximum: f32,
+    square_sum: f64,
+    sample_count: u64,
+}
+
+impl Default for BucketAccumulator {
+    fn default() -> Self {
+        Self {
+            minimum: f32::INFINITY,
+            maximum: f32::NEG_INFINITY,
+            square_sum: 0_f64,
+            sample_count: 0,
+        }
+    }
+}
+
+impl BucketAccumulator {
+    fn push(&mut self, sample: f32) {
+        self.minimum = self.minimum.min(sample);
+
+        self.maximum = self.maximum.max(sample);
+
+        self.square_sum += (sample as f64).powi(2);
+
+        self.sample_count += 1;
+    }
+
+    fn merge(&mut self, other: &Self) {
+        self.minimum = self.minimum.min(other.minimum);
+
+        self.maximum = self.maximum.max(other.maximum);
+
+        self.square_sum += other.square_sum;
+
+        self.sample_count += other.sample_count;
+    }
+
+    fn finish(&self) -> [f32; 3] {
+        if self.sample_count == 0 {
+            [0_f32, 0_f32, 0_f32]
+        } else {
+            [
+                self.minimum,
+                self.maximum,
+                (self.square_sum / self.sample_count as f64).sqrt() as f32,
+            ]
+        }
+    }
+}
+
+struct BucketGrid {
+    bucket_count: u32,
+    channel_count: usize,
+    frame_count: Option<u64>,
+    block_length: u64,
+    frame_index: u64,
+    accumulator_vector: Vec<BucketAccumulator>,
+}
+
+impl BucketGrid {
+    fn new(bucket_count: u32, channel_count: usize, frame_count: Option<u64>) -> Self {
+        let slot_count = match frame_count {
+            Some(_) => bucket_count as u64,
+            None => bucket_count as u64 * BLOCK_COUNT_FACTOR,
+        };
+
+        Self {
+            bucket_count,
+            channel_count,
+            frame_count,
+            block_length: 1,
+            frame_index: 0,
+            accumulator_vector: vec![
+                BucketAccumulator::default();
+                slot_count as usize * channel_count
+            ],
+        }
+    }
+
+    fn merge_block_pairs(&mut self) {
+        let block_vector_length = self.accumulator_vector.len();
+
+        self.accumulator_vector = self
+            .accumulator_vector
+            .chunks_exact(self.channel_count * 2)
+            .flat_map(|block_pair| {
+                let (block, next_block) = block_pair.split_at(self.channel_count);
+
+                block
+                    .iter()
+                    .zip(next_block)
+                    .map(|(accumulator, next_accumulator)| {
+                        let mut accumulator = *accumulator;
+
+                        accumulator.merge(next_accumulator);
+
+                        accumulator
+                    })
+            })
+            .collect();
+
+        self.accumulator_vector
+            .resize(block_vector_length, BucketAccumulator::default());
+
+        self.block_length *= 2;
+    }
+
+    fn push(&mut self, frame: &[f32]) {
+        let slot_index = match self.frame_count {
+            Some(frame_count) => (self.frame_index * self.bucket_count as u64 / frame_count)
+                .min(self.bucket_count as u64 - 1),
+            None => {
+                if self.frame_index / self.block_length
+                    >= self.bucket_count as u64 * BLOCK_COUNT_FACTOR
+                {
+                    self.merge_block_pairs();
+                }
+
+                self.frame_index / self.block_length
+            }
+        } as usize;
+
+        frame.iter().enumerate().for_each(|(channel, sample)| {
+            self.accumulator_vector[slot_index * self.channel_count + channel].push(*sample);
+        });
+
+        self.frame_index += 1;
+    }
+
+    fn finish(self) -> Vec<f32> {
+        let accumulator_vector = if self.frame_count.is_some() {
+            self.accumulator_vector
+        } else {
+            let mut bucket_vector =
+                vec![BucketAccumulator::default(); self.bucket_count as usize * self.channel_count];
+
+            self.accumulator_vector
+                .chunks_exact(self.channel_count)
+                .enumerate()
+                .for_each(|(block_index, block)| {
+                    let bucket_index =
+                        (block_index as u64 * self.block_length * self.bucket_count as u64
+                            / self.frame_index.max(1))
+                        .min(self.bucket_count as u64 - 1) as usize;
+
+                    block.iter().enumerate().for_each(|(channel, accumulator)| {
+                        bucket_vector[bucket_index * self.channel_count + channel]
+                            .merge(accumulator);
+                    });
+                });
+
+            bucket_vector
+        };
+
+        accumulator_vector
+            .iter()
+            .flat_map(BucketAccumulator::finish)
+            .collect()
+    }
+}
+
+pub struct Waveform {
+    bucket_count: u32,
+    channel_count: u32,
+    sample_rate: u32,
+    duration: f64,
+    data: Vec<f32>,
+}
+
+impl Waveform {
+    fn generate(path: &str, bucket_count: u32) -> Option<Self> {
+        let mut media_source = MediaSource::try_from(path).ok()?;
+
+        let sample_rate = media_source.codec_params.sample_rate?;
+
+        let time_base = media_source
+            .codec_params
+            .time_base
+            .unwrap_or(TimeBase::new(1, sample_rate));
+
+        let frame_count = media_source
+            .codec_params
+            .n_frames
+            .map(|timestamp| {
+                let Time { seconds, frac } = time_base.calc_time(timestamp);
+
+                ((seconds as f64 + frac) * sample_rate as f64).round() as u64
+            })
+            .filter(|frame_count| *frame_count > 0);
+
+        let mut bucket_grid: Option<BucketGrid> = None;
+
+        let mut sample_buffer: Option<SampleBuffer<f32>> = None;
+
+        loop {
+            match media_source.request_audio_buffer() {
+                Ok(Some(audio_buffer)) => {
+                    let spec = *audio_buffer.spec();
+
+                    let capacity = audio_buffer.capacity();
+
+                    let bucket_grid = bucket_grid.get_or_insert_with(|| {
+                        BucketGrid::new(bucket_count, spec.channels.count(), frame_count)
+                    });
+
+                    if bucket_grid.channel_count == 0
+                        || spec.channels.count() != bucket_grid.channel_count
+                    {
+                        continue;
+                    }
+
+                    if sample_buffer.as_ref().is_none_or(|sample_buffer| {
+                        sample_buffer.capacity() < capacity * bucket_grid.channel_count
+                    }) {
+                        sample_buffer = Some(SampleBuffer::new(capacity as u64, spec));
+                    }
+
+                    if let Some(sample_buffer) = sample_buffer.as_mut() {
+                        sample_buffer.copy_interleaved_ref(audio_buffer);
+
+                        sample_buffer
+                            .samples()
+                            .chunks_exact(bucket_grid.channel_count)
+                            .for_each(|frame| bucket_grid.push(frame));
+                    }
+                }
+                Ok(None) => {
+                    break;
+                }
+                Err(_) => {
+                    return None;
+                }
+            }
+        }
+
+        bucket_grid
+            .filter(|bucket_grid| bucket_grid.channel_count > 0)
+            .map(|bucket_grid| Self {
+                bucket_count,
+                channel_count: bucket_grid.channel_count as u32,
+                sample_rate,
+                duration: bucket_grid.frame_index as f64 / sample_rate as f64,
+                data: bucket_grid.finish(),
+            })
+    }
+
+    fn decode_cache(data: &[u8], bucket_count: u32) -> Option<Self> {
+        let (header, body) = data.split_at_checked(WAVEFORM_CACHE_HEADER_LENGTH)?;
+
+        let read_u32 = |offset: usize| {
+            header[offset..offset + 4]
+                .try_into()
+                .ok()
+                .map(u32::from_le_bytes)
+        };
+
+        let channel_count = read_u32(8)?;
+
+        (header.starts_with(WAVEFORM_CACHE_MAGIC)
+            && read_u32(4)? == bucket_count
+            && body.len() == bucket_count as usize * channel_count as usize * 3 * 4)
+            .then(|| Self {
+                bucket_count,
+                channel_count,
+                sample_rate: read_u32(12).unwrap_or(0),
+                duration: header[16..24]
+                    .try_into()
+                    .map(f64::from_le_bytes)
+                    .unwrap_or(0_f64),
+                data: body
+                    .chunks_exact(4)
+                    .map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+                    .collect(),
+            })
+    }
+
+    fn encode_cache(&self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(WAVEFORM_CACHE_HEADER_LENGTH + self.data.len() * 4);
+
+        data.extend_from_slice(WAVEFORM_CACHE_MAGIC);
+
+        data.extend_from_slice(&self.bucket_count.to_le_bytes());
+
+        data.extend_from_slice(&self.channel_count.to_le_bytes());
+
+        data.extend_from_slice(&self.sample_rate.to_le_bytes());
+
+        data.extend_from_slice(&self.duration.to_le_bytes());
+
+        self.data
+            .iter()
+            .for_each(|value| data.extend_from_slice(&value.to_le_bytes()));
+
+        data
+    }
+
+    fn request(path: &str, bucket_count: u32, cache_directory: Option<&str>) -> Option<Self> {
+        let cache_path = cache_directory.and_then(|cache_directory| {
+            SourceSignature::new(path).map(|signature| {
+                Path::new(cache_directory)
+                    .join(format!("{}.waveform", signature.get_digest(bucket_count)))
+            })
+        });
+
+        if let Some(waveform) = cache_path
+            .as_ref()
+            .and_then(|cache_path| fs::read(cache_path).ok())
+            .and_then(|data| Self::decode_cache(&data, bucket_count))
+        {
+            return Some(waveform);
+        }
+
+        let waveform = Self::generate(path, bucket_count)?;
+
+        if let (Some(cache_directory), Some(cache_path)) = (cache_directory, cache_path) {
+            let _ = fs::create_dir_all(cache_directory)
+                .and_then(|_| fs::write(cache_path, waveform.encode_cache()));
+        }
+
+        Some(waveform)
+    }
+
+    fn into_object(self, env: &Env) -> Result<JsObject> {
+        let mut waveform = env.create_object()?;
+
+        waveform.set("bucketCount", self.bucket_count)?;
+
+        waveform.set("channelCount", self.channel_count)?;
+
+        waveform.set("sampleRate", self.sample_rate)?;
+
+        waveform.set("duration", self.duration)?;
+
+        waveform.set("data", Float32Array::new(self.data))?;
+
+        Ok(waveform)
+    }
+}
+
+pub struct WaveformTask {
+    path: String,
+    bucket_count: u32,
+    cache_directory: Option<String>,
+}
+
+impl Task for WaveformTask {
+    type Output = Option<Waveform>;
+    type JsValue = Option<JsObject>;
+
+    fn compute(&mut self) -> Result<Self::Output> {
+        Ok(Waveform::request(
+            &self.path,
+            self.bucket_count,
+            self.cache_directory.as_deref(),
+        ))
+    }
+
+    fn resolve(&mut self, env: Env, output: Self::Output) -> Result<Self::JsValue> {
+        output
+            .map(|waveform| waveform.into_object(&env))
+            .transpose()
+    }
+}
+
+#[cfg_attr(test, allow(dead_code))]
+#[napi(ts_return_type = "Promise<null | { \
+    bucketCount: number, channelCount: number, sampleRate: number, duration: number, data: Float32Array \
+    }>")]
+pub fn generate_waveform(
+    path: String,
+    bucket_count: u32,
+    cache_directory: Option<String>,
+) -> Result<AsyncTask<WaveformTask>> {
+    if (1..=MAX_BUCKET_COUNT).contains(&bucket_count) {
+        Ok(AsyncTask::new(WaveformTask {
+            path,
+            bucket_count,
+            cache_directory,
+        }))
+    } else {
+        Err(Error::new(
+            Status::InvalidArg,
+            format!(
+                "The bucket count must be between 1 and {}",
+                MAX_BUCKET_COUNT
+            ),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fill(bucket_grid: &mut BucketGrid, frame_count: usize) {
+        (0..frame_count).for_each(|frame_index| {
+            let sample = if frame_index < frame_count / 2 {
+                0.5_f32
+            } else {
+                -0.25_f32
+            };
+
+            bucket_grid.push(&[sample, -sample]);
+        });
+    }
+
+    #[test]
+    fn known_length_fills_every_bucket() {
+        let mut bucket_grid = BucketGrid::new(8, 2, Some(1_000));
+
+        fill(&mut bucket_grid, 1_000);
+
+        let data = bucket_grid.finish();
+
+        assert_eq!(data.len(), 8 * 2 * 3);
+
+        assert_eq!(
+            &data[0..6],
+            &[0.5_f32, 0.5_f32, 0.5_f32, -0.5_f32, -0.5_f32, 0.5_f32]
+        );
+
+        assert_eq!(
+            &data[42..48],
+            &[-0.25_f32, -0.25_f32, 0.25_f32, 0.25_f32, 0.25_f32, 0.25_f32]
+        );
+    }
+
+    #[test]
+    fn unknown_length_matches_known_length() {
+        let mut known_grid = BucketGrid::new(8, 2, Some(131_072));
+
+        let mut unknown_grid = BucketGrid::new(8, 2, None);
+
+        fill(&mut known_grid, 131_072);
+
+        fill(&mut unknown_grid, 131_072);
+
+        assert!(unknown_grid.block_length > 1);
+
+        assert_eq!(unknown_grid.frame_index, 131_072);
+
+        let known_data = known_grid.finish();
+
+        let unknown_data = unknown_grid.finish();
+
+        assert_eq!(unknown_data.len(), known_data.len());
+
+        known_data
+            .chunks_exact(3)
+            .zip(unknown_data.chunks_exact(3))
+            .for_each(|(known_bucket, unknown_bucket)| {
+                assert_eq!(known_bucket[0], unknown_bucket[0]);
+
+                assert_eq!(known_bucket[1], unknown_bucket[1]);
+            });
+    }
+
+    #[test]
+    fn short_source_leaves_trailing_buckets_empty() {
+        let mut bucket_grid = BucketGrid::new(8, 1, None);
+
+        (0..3).for_each(|_| bucket_grid.push(&[1_f32]));
+
+        let data = bucket_grid.finish();
+
+        assert_eq!(data.len(), 8 * 3);
+
+        assert!(
+            data.chunks_exact(3)
+                .any(|bucket| bucket == [1_f32, 1_f32, 1_f32])
+        );
+
+        assert!(
+            data.chunks_exact(3)
+                .any(|bucket| bucket == [0_f32, 0_f32, 0_f32])
+        );
+    }
+
+    #[test]
+    fn cache_round_trips_header_and_data() {
+        let waveform = Waveform {
+            bucket_count: 2,
+            channel_count: 1,
+            sample_rate: 44_100,
+            duration: 12.5_f64,
+            data: vec![-0.5_f32, 0.5_f32, 0.25_f32, -1_f32, 1_f32, 0.75_f32],
+        };
+
+        let data = waveform.encode_cache();
+
+        assert_eq!(data.len(), WAVEFORM_CACHE_HEADER_LENGTH + 6 * 4);
+
+        assert!(data.starts_with(WAVEFORM_CACHE_MAGIC));
+
+        let decoded = Waveform::decode_cache(&data, 2).unwrap();
+
+        assert_eq!(decoded.bucket_count, 2);
+
+        assert_eq!(decoded.channel_count, 1);
+
+        assert_eq!(decoded.sample_rate, 44_100);
+
+        assert_eq!(decoded.duration, 12.5_f64);
+
+        assert_eq!(decoded.data, waveform.data);
+    }
+
+    #[test]
+    fn cache_rejects_mismatched_or_truncated_data() {
+        let data = Waveform {
+            bucket_count: 2,
+            channel_count: 1,
+            sample_rate: 44_100,
+            duration: 1_f64,
+            data: vec![0_f32; 6],
+        }
+        .encode_cache();
+
+        assert!(Waveform::decode_cache(&data, 4).is_none());
+
+        assert!(Waveform::decode_cache(&data[..data.len() - 4], 2).is_none());
+
+        assert!(Waveform::decode_cache(&data[..WAVEFORM_CACHE_HEADER_LENGTH - 1], 2).is_none());
+
+        let mut corrupted = data.clone();
+
+        corrupted[0] = b'X';
+
+        assert!(Waveform::decode_cache(&corrupted, 2).is_none());
+    }
+}
@@ -103,9 +103,12 @@ export class DeviceManager extends EventEmitter {
  * 'device-silent-exception':[],
  * 'source-invalid-file':[uuid:string],
  * 'source-incorrect-file':[uuid:string],
- * 'track':[uuid:string],
+ * 'track':[uuid:string,gain:null|number],
+ * 'duration':[second:null|number],
  * 'progress':[second:number],
  * 'state':[isPlaying:boolean],
+ * 'volume':[amplitude:number,db:null|number],
+ * 'reduction':[compressorDb:number,limiterDb:number],
  * 'finish':[],
  * }>}
  */
@@ -243,13 +246,17 @@ export class Player extends EventEmitter {
                 case 'track':
                     {
                         /**
-                         * @type {string}
+                         * @type {{identifier:string,gain:null|number}}
                          */
-                        const uuid = JSON.parse(dataJSON)
+                        const { identifier: uuid, gain } = JSON.parse(dataJSON)
 
-                        this.emit('track', uuid === 'NIL' ? nil : uuid)
+                        this.emit('track', uuid === 'NIL' ? nil : uuid, gain)
                     }
 
+                    break
+                case 'duration':
+                    this.emit('duration', JSON.parse(dataJSON))
+
                     break
                 case 'progress':
                     this.emit('progress', JSON.parse(dataJSON))
@@ -270,6 +277,28 @@ export class Player extends EventEmitter {
                         ],
                     )
 
+                    break
+                case 'volume':
+                    {
+                        /**
+                         * @type {{amplitude:number,db:null|number}}
+                         */
+                        const { amplitude, db } = JSON.parse(dataJSON)
+
+                        this.emit('volume', amplitude, db)
+                    }
+
+                    break
+                case 'reduction':
+                    {
+                        /**
+                         * @type {{compressor:number,limiter:number}}
+                         */
+                        const { compressor, limiter } = JSON.parse(dataJSON)
+
+                        this.emit('reduction', compressor, limiter)
+                    }
+
                     break
                 case 'finish':
                     this.emit('finish')
@@ -291,13 +320,20 @@ export class Player extends EventEmitter {
      * @type {(mode:'default'|{
      * category:'custom',
      * id:string,
-     * },volume:number)=>void}
+     * },volume:number,stereoImage?:import("./player.d.ts").StereoImageOption)=>void}
      */
-    selectMode(mode, volume) {
+    selectMode(mode, volume, stereoImage) {
         const { category, id } =
             mode === 'default' ? { category: mode, id: '' } : mode
 
-        this.#player?.selectMode(category, id, volume)
+        this.#player?.selectMode(category, id, volume, stereoImage)
+    }
+
+    /**
+     * @type {(stereoImage:import("./player.d.ts").StereoImageOption)=>void}
+     */
+    setStereoImage(stereoImage) {
+        this.#player?.setStereoImage(stereoImage)
     }
 
     /**
@@ -307,6 +343,166 @@ export class Player extends EventEmitter {
         this.#player?.modifyVolume(value)
     }
 
+    /**
+     * @type {(db:number)=>void}
+     */
+    setVolumeDb(db) {
+        this.#player?.setVolumeDb(db)
+    }
+
+    /**
+     * @type {(curve:'logarithmic'|'cubic'|'linear'|'table',rangeDb?:number,dbTable?:number[])=>void}
+     */
+    setVolumeCurve(curve, rangeDb, dbTable) {
+        this.#player?.setVolumeCurve(curve, rangeDb, dbTable)
+    }
+
+    /**
+     * @type {(fadeInMs:number,fadeOutMs:number,volumeMs?:number)=>void}
+     */
+    setRampDuration(fadeInMs, fadeOutMs, volumeMs) {
+        this.#player?.setRampDuration(fadeInMs, fadeOutMs, volumeMs)
+    }
+
+    /**
+     * @type {(second:number,curve:'linear'|'equal-power'|'s-curve')=>void}
+     */
+    setCrossfade(second, curve) {
+        this.#player?.setCrossfade(second, curve)
+    }
+
+    /**
+     * @type {(mode:'off'|'track'|'album',preampDb:number,preventClipping:boolean)=>void}
+     */
+    setReplayGainMode(mode, preampDb, preventClipping) {
+        this.#player?.setReplayGainMode(mode, preampDb, preventClipping)
+    }
+
+    /**
+     * @type {(enabled:boolean)=>void}
+     */
+    setUpmix(enabled) {
+        this.#player?.setUpmix(enabled)
+    }
+
+    /**
+     * @type {(mode:'none'|'tpdf'|'shaped')=>void}
+     */
+    setDither(mode) {
+        this.#player?.setDither(mode)
+    }
+
+    /**
+     * @type {(enabled:boolean,ceilingDb?:number)=>void}
+     */
+    setLimiter(enabled, ceilingDb) {
+        this.#player?.setLimiter(enabled, ceilingDb)
+    }
+
+    /**
+     * @type {(matrix:null|number[][])=>void}
+     */
+    setDownmixMatrix(matrix) {
+        this.#player?.setDownmixMatrix(matrix)
+    }
+
+    /**
+     * @type {(preset:null|{
+     * preamp:number,
+     * bandList:Array<{
+     * kind:'peaking'|'low-shelf'|'high-shelf'|'low-pass'|'high-pass'|'notch',
+     * frequency:number,
+     * gain:number,
+     * q:number,
+     * }>,
+     * })=>void}
+     */
+    setEqualizer(preset) {
+        this.configureDspStage('equalizer', preset)
+    }
+
+    /**
+     * @type {(preset:null|import("./player.d.ts").CrossfeedPreset)=>void}
+     */
+    setCrossfeed(preset) {
+        this.configureDspStage('crossfeed', preset)
+    }
+
+    /**
+     * @type {(preset:null|import("./player.d.ts").CompressorPreset)=>void}
+     */
+    setCompressor(preset) {
+        this.configureDspStage('compressor', preset)
+    }
+
+    /**
+     * @type {(rate:number,mode?:'varispeed'|'preserve-pitch')=>void}
+     */
+    setPlaybackRate(rate, mode) {
+        this.#player?.setPlaybackRate(rate, mode)
+    }
+
+    /**
+     * @type {(quality:'fast'|'balanced'|'best')=>void}
+     */
+    setResampleQuality(quality) {
+        this.#player?.setResampleQuality(quality)
+    }
+
+    /**
+     * @type {(startMs:number|null,endMs:number|null)=>void}
+     */
+    setLoop(startMs, endMs) {
+        this.#player?.setLoop(startMs, endMs)
+    }
+
+    /**
+     * @type {(enabled:boolean,count?:number,fadeSecond?:number)=>void}
+     */
+    setTagLoop(enabled, count, fadeSecond) {
+        this.#player?.setTagLoop(enabled, count, fadeSecond)
+    }
+
+    /**
+     * @type {(stageList:Array<{id:string,enabled:boolean}>)=>void}
+     */
+    setDspChain(stageList) {
+        this.#player?.setDspChain(stageList)
+    }
+
+    /**
+     * @type {(id:'equalizer'|'crossfeed'|'compressor',config:any)=>void}
+     */
+    configureDspStage(id, config) {
+        this.#player?.configureDspStage(id, config)
+    }
+
+    /**
+     * @type {(enabled:boolean)=>void}
+     */
+    setDspBypass(enabled) {
+        this.#player?.setDspBypass(enabled)
+    }
+
+    /**
+     * @type {(handler:null|((analysis:{
+     * progress:number,
+     * peak:[number,number],
+     * rms:[number,number],
+     * clip:[boolean,boolean],
+     * spectrum:number[],
+     * })=>void),bandCount?:number,refreshRate?:number)=>void}
+     */
+    setAnalysisHandler(handler, bandCount, refreshRate) {
+        this.#player?.setAnalysisHandler(
+            handler
+                ? (_, dataJSON) => handler(JSON.parse(dataJSON))
+                : null,
+            bandCount,
+            refreshRate,
+        )
+    }
+
     /**
      * @type {(path:string,identifier:string)=>void}
      */
@@ -314,6 +510,13 @@ export class Player extends EventEmitter {
         this.#player?.selectFile(path, identifier)
     }
 
+    /**
+     * @type {(path:string,identifier:string)=>void}
+     */
+    queueNext(path, identifier) {
+        this.#player?.queueNext(path, identifier)
+    }
+
     /**
      * @type {(second:number)=>void}
      */
@@ -20,4 +20,75 @@ export const requestDeviceManager = () =>
  * @type {()=>Player}
  */
 export const requestPlayer = () =>
-    new Player(moduleWrapper.core.Player)
\ No newline at end of file
+    new Player(moduleWrapper.core.Player)
+
+/**
+ * @type {(path:string)=>Promise<null|{
+ * title:null|string,
+ * artist:null|string,
+ * album:null|string,
+ * albumArtist:null|string,
+ * trackNumber:null|number,
+ * trackTotal:null|number,
+ * discNumber:null|number,
+ * discTotal:null|number,
+ * genre:null|string,
+ * date:null|string,
+ * lyrics:null|string,
+ * replayGain:{trackGain:null|number,trackPeak:null|number,albumGain:null|number,albumPeak:null|number},
+ * codec:null|string,
+ * bitDepth:null|number,
+ * sampleRate:null|number,
+ * channelCount:null|number,
+ * bitrate:null|number,
+ * duration:null|number,
+ * pictureList:Array<{mediaType:string,usage:null|string,width:null|number,height:null|number,byteLength:number}>,
+ * }>}
+ */
+export const readMetadata = path => moduleWrapper.core.readMetadata(path)
+
+/**
+ * @type {(path:string,thumbnailSize?:number,cacheDirectory?:string)=>Promise<null|{
+ * origin:'embedded'|'folder',
+ * mediaType:string,
+ * data:Buffer,
+ * thumbnail:null|{mediaType:'image/jpeg'|'image/png',width:number,height:number,data:Buffer,path:null|string},
+ * }>}
+ */
+export const extractArtwork = (path, thumbnailSize, cacheDirectory) =>
+    moduleWrapper.core.extractArtwork(path, thumbnailSize, cacheDirectory)
+
+/**
+ * @type {(path:string,bucketCount:number,cacheDirectory?:string)=>Promise<null|{
+ * bucketCount:number,
+ * channelCount:number,
+ * sampleRate:number,
+ * duration:number,
+ * data:Float32Array,
+ * }>}
+ */
+export const generateWaveform = (path, bucketCount, cacheDirectory) =>
+    moduleWrapper.core.generateWaveform(path, bucketCount, cacheDirectory)
+
+/**
+ * @type {(pathList:Array<string>,progressHandler?:(error:null|Error,completed:number,total:number,path:string)=>void)=>Promise<{
+ * trackList:Array<null|{integratedLoudness:null|number,loudnessRange:null|number,samplePeak:number,truePeak:number,gain:null|number}>,
+ * album:null|{integratedLoudness:null|number,loudnessRange:null|number,samplePeak:number,truePeak:number,gain:null|number},
+ * }>}
+ */
+export const analyzeLoudness = (pathList, progressHandler) =>
+    moduleWrapper.core.analyzeLoudness(pathList, progressHandler)
+
+/**
+ * @type {(text:string)=>null|{
+ * preamp:number,
+ * bandList:Array<{
+ * kind:'peaking'|'low-shelf'|'high-shelf'|'low-pass'|'high-pass'|'notch',
+ * frequency:number,
+ * gain:number,
+ * q:number,
+ * }>,
+ * }}
+ */
+export const parseEqualizerPreset = text =>
+    moduleWrapper.core.parseEqualizerPreset(text)